@@ -0,0 +1,22 @@
+use std::io::{self, Read};
+use std::process;
+
+use screen::common::assemble;
+
+// Reads a CRT image (rows of '#' and '.') on stdin and prints a program that
+// draws it.
+fn main() {
+    let mut image = String::new();
+    io::stdin().read_to_string(&mut image).unwrap();
+    match assemble(&image) {
+        Ok(program) => {
+            for instruction in program {
+                println!("{instruction}");
+            }
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            process::exit(1);
+        }
+    }
+}
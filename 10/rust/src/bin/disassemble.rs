@@ -0,0 +1,17 @@
+use std::io::{self, Read};
+use std::process;
+
+use screen::common::{disassemble, parse_program};
+
+// Reads a program on stdin and prints it with cycle numbers and X values.
+fn main() {
+    let mut source = String::new();
+    io::stdin().read_to_string(&mut source).unwrap();
+    match parse_program(&source) {
+        Ok(program) => print!("{}", disassemble(&program)),
+        Err(err) => {
+            eprintln!("{err}");
+            process::exit(1);
+        }
+    }
+}
@@ -39,7 +39,7 @@ fn main() {
         }
         pos += 1;
         if pos >= line {
-            println!("");
+            println!();
             pos = 0;
         }
 
@@ -0,0 +1,204 @@
+use std::fmt;
+
+pub const CRT_WIDTH: usize = 40;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    Noop,
+    Addx(i32),
+}
+
+impl Instruction {
+    pub fn cycles(&self) -> usize {
+        match self {
+            Instruction::Noop => 1,
+            Instruction::Addx(_) => 2,
+        }
+    }
+
+    pub fn parse(line: &str) -> Result<Instruction, String> {
+        let tokens = line.split_whitespace().collect::<Vec<&str>>();
+        match tokens[..] {
+            ["noop"] => Ok(Instruction::Noop),
+            ["addx", value] => value
+                .parse::<i32>()
+                .map(Instruction::Addx)
+                .map_err(|err| format!("bad addx operand '{value}': {err}")),
+            _ => Err(format!("unknown instruction '{}'", line.trim())),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Noop => write!(f, "noop"),
+            Instruction::Addx(value) => write!(f, "addx {value}"),
+        }
+    }
+}
+
+// Blank lines are skipped, anything else must be a valid instruction.
+pub fn parse_program(source: &str) -> Result<Vec<Instruction>, String> {
+    source
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(num, line)| {
+            Instruction::parse(line).map_err(|err| format!("line {}: {err}", num + 1))
+        })
+        .collect()
+}
+
+// Value of the X register *during* each cycle; cycle 1 is at index 0. X only
+// changes once an `addx` has completed its second cycle.
+pub fn trace(program: &[Instruction]) -> Vec<i32> {
+    let mut reg_x = 1;
+    let mut xs = Vec::new();
+    for instruction in program {
+        for _ in 0..instruction.cycles() {
+            xs.push(reg_x);
+        }
+        if let Instruction::Addx(value) = instruction {
+            reg_x += value;
+        }
+    }
+    xs
+}
+
+fn lit(cycle: usize, reg_x: i32) -> bool {
+    let pos = (cycle % CRT_WIDTH) as i32;
+    pos >= reg_x - 1 && pos <= reg_x + 1
+}
+
+// What the CRT shows while running `program`, one row of `CRT_WIDTH` pixels
+// per line.
+pub fn render(program: &[Instruction]) -> String {
+    let mut screen = String::new();
+    for (cycle, reg_x) in trace(program).into_iter().enumerate() {
+        screen.push(if lit(cycle, reg_x) { '#' } else { '.' });
+        if (cycle + 1) % CRT_WIDTH == 0 {
+            screen.push('\n');
+        }
+    }
+    screen
+}
+
+// Candidate values for X go just far enough past each edge of the screen to
+// light nothing: -2 lights [-3, -1] and 41 lights [40, 42].
+const X_MIN: i32 = -2;
+const X_MAX: i32 = CRT_WIDTH as i32 + 1;
+const X_COUNT: usize = (X_MAX - X_MIN + 1) as usize;
+
+fn parse_image(image: &str) -> Result<Vec<bool>, String> {
+    let mut pixels = Vec::new();
+    for (num, line) in image.lines().enumerate() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+        if line.len() != CRT_WIDTH {
+            return Err(format!(
+                "line {}: expected {CRT_WIDTH} pixels, found {}",
+                num + 1,
+                line.len()
+            ));
+        }
+        for ch in line.chars() {
+            match ch {
+                '#' => pixels.push(true),
+                '.' => pixels.push(false),
+                _ => return Err(format!("line {}: unexpected pixel '{ch}'", num + 1)),
+            }
+        }
+    }
+    Ok(pixels)
+}
+
+// Produce a `noop`/`addx` program that draws `image` (rows of `#` and `.`, as
+// printed by part 2).
+//
+// X is constant over runs of cycles and can only change right after an `addx`,
+// which takes 2 cycles, so each run that is followed by a change must last at
+// least 2 cycles: it is assembled as `noop`s followed by one `addx`. The last
+// run is only `noop`s.
+//
+// `feasible[cycle][x][fresh]` tells whether the rest of the image can still be
+// drawn when X is `x` during `cycle`, `fresh` being set when X just changed
+// (the run is 1 cycle long so far and X can't change again yet). We fill it
+// backward, then walk forward keeping X for as long as possible.
+pub fn assemble(image: &str) -> Result<Vec<Instruction>, String> {
+    let pixels = parse_image(image)?;
+    if pixels.is_empty() {
+        return Ok(Vec::new());
+    }
+    let index = |x: i32| (x - X_MIN) as usize;
+    let value = |k: usize| k as i32 + X_MIN;
+
+    let cycles = pixels.len();
+    let mut feasible = vec![[[false; 2]; X_COUNT]; cycles];
+    for cycle in (0..cycles).rev() {
+        for k in 0..X_COUNT {
+            if lit(cycle, value(k)) != pixels[cycle] {
+                continue;
+            }
+            if cycle + 1 == cycles {
+                feasible[cycle][k] = [true, true];
+                continue;
+            }
+            let next = &feasible[cycle + 1];
+            let keep = next[k][0];
+            let change = (0..X_COUNT).any(|other| other != k && next[other][1]);
+            feasible[cycle][k] = [keep || change, keep];
+        }
+    }
+
+    // X starts at 1, a run which only begins at cycle 1
+    let mut k = index(1);
+    if !feasible[0][k][1] {
+        return Err("image can't be drawn by any program".to_string());
+    }
+    let mut program = Vec::new();
+    let mut run_start = 0;
+    for cycle in 0..cycles - 1 {
+        let next = &feasible[cycle + 1];
+        if next[k][0] {
+            continue;
+        }
+        let other = (0..X_COUNT)
+            .find(|other| *other != k && next[*other][1])
+            .unwrap(); // guaranteed by `feasible`
+        program.extend((run_start..cycle - 1).map(|_| Instruction::Noop));
+        program.push(Instruction::Addx(value(other) - value(k)));
+        run_start = cycle + 1;
+        k = other;
+    }
+    program.extend((run_start..cycles).map(|_| Instruction::Noop));
+    Ok(program)
+}
+
+// Annotate each instruction with the cycle(s) it runs for and the value of X
+// during those cycles (and after, for `addx`).
+pub fn disassemble(program: &[Instruction]) -> String {
+    let mut listing = String::new();
+    let mut reg_x = 1;
+    let mut cycle = 1;
+    for instruction in program {
+        let text = instruction.to_string();
+        let line = match instruction {
+            Instruction::Noop => format!("{text:<10} ; cycle  {cycle:>3}      X={reg_x}"),
+            Instruction::Addx(value) => format!(
+                "{text:<10} ; cycles {cycle:>3}-{:<3}  X={reg_x} -> {}",
+                cycle + 1,
+                reg_x + value
+            ),
+        };
+        listing.push_str(&line);
+        listing.push('\n');
+        cycle += instruction.cycles();
+        if let Instruction::Addx(value) = instruction {
+            reg_x += value;
+        }
+    }
+    listing
+}
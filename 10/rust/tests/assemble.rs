@@ -0,0 +1,34 @@
+use screen::common::*;
+
+// The image drawn by the puzzle's example program
+fn example_image() -> String {
+    let source = include_str!("../../example.txt");
+    render(&parse_program(source).unwrap())
+}
+
+#[test]
+fn round_trip() {
+    let image = example_image();
+    let program = assemble(&image).unwrap();
+    assert_eq!(render(&program), image);
+
+    // The listing holds the same instructions, annotated after the ';'
+    let listing = disassemble(&program)
+        .lines()
+        .map(|line| line.split(';').next().unwrap().to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_eq!(parse_program(&listing).unwrap(), program);
+}
+
+#[test]
+fn infeasible_image() {
+    // X starts at 1, so the sprite covers the first pixel for at least the
+    // first 2 cycles
+    let mut image = example_image();
+    image.replace_range(..1, ".");
+    assert_eq!(
+        assemble(&image),
+        Err("image can't be drawn by any program".to_string())
+    );
+}
@@ -0,0 +1,16 @@
+use std::io::{self, Read};
+
+use monkey::common::MonkeyBusiness;
+
+fn main() {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input).unwrap();
+    let mut monkey_business = MonkeyBusiness::parse(&input).unwrap_or_else(|err| panic!("{err}"));
+
+    monkey_business
+        .play(20)
+        .unwrap_or_else(|err| panic!("{err}"));
+    let (x, y) = monkey_business.most_active();
+    let xy = x * y;
+    println!("{x} * {y} = {xy}");
+}
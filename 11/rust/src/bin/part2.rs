@@ -0,0 +1,28 @@
+use std::env;
+use std::io::{self, Read};
+
+use monkey::common::{MonkeyBusiness, WorryPolicy};
+
+// Takes an optional number of rounds (10000 by default); fast-forwarding makes
+// even billions of rounds quick.
+fn main() {
+    let rounds: usize = env::args()
+        .nth(1)
+        .map(|arg| {
+            arg.parse()
+                .expect("Error 1st positional argument: expect usize")
+        })
+        .unwrap_or(10000);
+
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input).unwrap();
+    let mut monkey_business = MonkeyBusiness::parse(&input).unwrap_or_else(|err| panic!("{err}"));
+
+    monkey_business.set_policy(WorryPolicy::Modulo);
+    monkey_business
+        .fast_forward(rounds)
+        .unwrap_or_else(|err| panic!("{err}"));
+    let (x, y) = monkey_business.most_active();
+    let xy = i128::from(x) * i128::from(y); // overflows i64 after ~1e9 rounds
+    println!("{x} * {y} = {xy}");
+}
@@ -0,0 +1,48 @@
+use std::env;
+use std::io::{self, Read};
+use std::process;
+
+use monkey::common::{MonkeyBusiness, WorryPolicy};
+
+fn exit_with(err: &str) -> ! {
+    eprintln!("{err}");
+    process::exit(1);
+}
+
+// Plays some rounds and dumps the resulting state as JSON on stdout. Reads
+// either puzzle notes or a previous dump on stdin, so a game can be carried on
+// from where it stopped:
+//
+//   play 10 modulo < ../example.txt > state.json
+//   play 10 < state.json
+fn main() {
+    let rounds: usize = env::args()
+        .nth(1)
+        .expect("Missing 1st positional argument: rounds to play")
+        .parse()
+        .expect("Error 1st positional argument: expect usize");
+    let policy = env::args().nth(2).map(|arg| match arg.as_str() {
+        "relief" => WorryPolicy::Relief,
+        "modulo" => WorryPolicy::Modulo,
+        _ => panic!("Error 2nd positional argument: expect 'relief' or 'modulo'"),
+    });
+
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input).unwrap();
+    let monkey_business = if input.trim_start().starts_with('{') {
+        MonkeyBusiness::from_json(&input)
+    } else {
+        MonkeyBusiness::parse(&input)
+    };
+    let mut monkey_business = monkey_business.unwrap_or_else(|err| exit_with(&err));
+    if let Some(policy) = policy {
+        monkey_business.set_policy(policy);
+    }
+
+    monkey_business
+        .play(rounds)
+        .unwrap_or_else(|err| exit_with(&err));
+    eprintln!("After round {}:", monkey_business.rounds());
+    eprint!("{monkey_business}");
+    println!("{}", serde_json::to_string(&monkey_business).unwrap());
+}
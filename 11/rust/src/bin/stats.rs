@@ -0,0 +1,50 @@
+use std::env;
+use std::io::{self, Read};
+
+use monkey::common::{stats, MonkeyBusiness, WorryPolicy};
+
+// Plays some rounds and exports statistics for every N rounds on stdout:
+//
+//   stats 10000 modulo csv 100 < ../example.txt > activity.csv
+//
+// Formats are "csv" (one line per round and monkey), "throws" (one line per
+// throw, in CSV) and "json" (everything).
+fn main() {
+    let mut args = env::args().skip(1);
+    let rounds: usize = args
+        .next()
+        .expect("Missing 1st positional argument: rounds to play")
+        .parse()
+        .expect("Error 1st positional argument: expect usize");
+    let policy = match args.next().as_deref() {
+        Some("relief") | None => WorryPolicy::Relief,
+        Some("modulo") => WorryPolicy::Modulo,
+        _ => panic!("Error 2nd positional argument: expect 'relief' or 'modulo'"),
+    };
+    let format = args.next().unwrap_or_else(|| "csv".to_string());
+    let every: usize = args
+        .next()
+        .map(|arg| {
+            arg.parse()
+                .expect("Error 4th positional argument: expect usize")
+        })
+        .unwrap_or(1);
+
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input).unwrap();
+    let mut monkey_business = MonkeyBusiness::parse(&input).unwrap_or_else(|err| panic!("{err}"));
+    monkey_business.set_policy(policy);
+
+    let round_stats = monkey_business
+        .play_with_stats(rounds, every)
+        .unwrap_or_else(|err| panic!("{err}"));
+    match format.as_str() {
+        "csv" => print!("{}", stats::to_csv(&round_stats)),
+        "throws" => print!("{}", stats::throws_to_csv(&round_stats)),
+        "json" => println!("{}", stats::to_json(&round_stats)),
+        _ => panic!("Error 3rd positional argument: expect 'csv', 'throws' or 'json'"),
+    }
+    for (monkey, inspected) in monkey_business.top_active(3) {
+        eprintln!("Monkey {monkey} inspected items {inspected} times.");
+    }
+}
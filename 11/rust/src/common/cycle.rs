@@ -0,0 +1,124 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::{MonkeyBusiness, WorryManager, WorryPolicy};
+
+// Under the modulo policy, items don't interact with each other: where an item
+// goes next only depends on which monkey holds it and its worry level, and
+// there is a finite number of (holder, worry mod LCM) states. So each item's
+// state at the start of a round is eventually periodic.
+//
+// We play each item alone, round after round, until its state repeats. The
+// inspections it causes over any number of rounds are then:
+//
+//   counts(rounds) = counts(start) + cycles * counts(one cycle) + counts(rest)
+//
+// where `start` is the round the cycle starts at. This is exact, and as fast
+// for a billion rounds as it is for a thousand.
+//
+// The order in which items are queued isn't tracked: after a fast-forward,
+// each monkey holds its items in the order they were held at the start.
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct ItemState {
+    holder: usize,
+    worry: i64,
+}
+
+impl MonkeyBusiness {
+    // Plays one round for a single item, adding its inspections to `counts`.
+    fn item_round(
+        &self,
+        mut state: ItemState,
+        manager: WorryManager,
+        counts: &mut [i64],
+    ) -> Result<ItemState, String> {
+        loop {
+            let monkey = &self.monkeys[state.holder];
+            let worry = manager
+                .inspect(&monkey.operation, state.worry)
+                .ok_or_else(|| {
+                    format!(
+                        "monkey {}: worry level {} overflowed",
+                        state.holder, state.worry
+                    )
+                })?;
+            counts[state.holder] += 1;
+            let dest = if worry % monkey.test_value == 0 {
+                monkey.dest_true
+            } else {
+                monkey.dest_false
+            };
+            // Monkeys after the thrower still play this round
+            let done = dest < state.holder;
+            state = ItemState {
+                holder: dest,
+                worry,
+            };
+            if done {
+                return Ok(state);
+            }
+        }
+    }
+
+    // Inspections caused by one item over `rounds`, and where it ends up.
+    fn item_fast_forward(
+        &self,
+        mut state: ItemState,
+        rounds: usize,
+        manager: WorryManager,
+    ) -> Result<(Vec<i64>, ItemState), String> {
+        let mut seen = HashMap::<ItemState, usize>::new();
+        // `history[r]` are the counts after `r` rounds, `states[r]` the state
+        // at the start of round `r + 1`.
+        let mut history = vec![vec![0; self.monkeys.len()]];
+        let mut states = vec![state];
+        for round in 0..rounds {
+            if let Some(&start) = seen.get(&state) {
+                let length = round - start;
+                let cycles = ((rounds - start) / length) as i64;
+                let rest = (rounds - start) % length;
+                let counts = (0..self.monkeys.len())
+                    .map(|m| {
+                        let cycle = history[start + length][m] - history[start][m];
+                        let extra = history[start + rest][m] - history[start][m];
+                        history[start][m] + cycles * cycle + extra
+                    })
+                    .collect();
+                return Ok((counts, states[start + rest]));
+            }
+            seen.insert(state, round);
+            let mut counts = history[round].clone();
+            state = self.item_round(state, manager, &mut counts)?;
+            history.push(counts);
+            states.push(state);
+        }
+        Ok((history.pop().unwrap(), state))
+    }
+
+    // Same outcome as `play` (item order in queues aside, see above), but only
+    // available with the modulo policy.
+    pub fn fast_forward(&mut self, rounds: usize) -> Result<(), String> {
+        if self.policy != WorryPolicy::Modulo {
+            return Err("fast-forward needs the modulo worry policy".to_string());
+        }
+        let manager = self.worry_manager()?;
+        let mut queues = vec![VecDeque::new(); self.monkeys.len()];
+        let mut totals = vec![0; self.monkeys.len()];
+        for (holder, items) in self.queues.iter().enumerate() {
+            for &worry in items {
+                let (counts, state) =
+                    self.item_fast_forward(ItemState { holder, worry }, rounds, manager)?;
+                for (total, count) in totals.iter_mut().zip(counts) {
+                    *total += count;
+                }
+                queues[state.holder].push_back(state.worry);
+            }
+        }
+        for (monkey, total) in self.monkeys.iter_mut().zip(totals) {
+            monkey.inspected += total;
+        }
+        self.queues = queues;
+        self.rounds += rounds;
+        Ok(())
+    }
+}
@@ -1,69 +1,202 @@
+pub mod cycle;
+pub mod parse;
+pub mod stats;
+
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use stats::{RoundStats, Throw};
+
+// The right-hand side of "Operation: new = ..." in the puzzle input. It prints
+// and parses as it's written there, e.g. "old * 19".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Operation {
+    Add(i64),      // old + n
+    Subtract(i64), // old - n
+    Multiply(i64), // old * n
+    Double,        // old + old
+    Square,        // old * old
+}
+
+impl Operation {
+    // None if the result doesn't fit in an i64.
+    pub fn checked_apply(&self, old: i64) -> Option<i64> {
+        match self {
+            Operation::Add(value) => old.checked_add(*value),
+            Operation::Subtract(value) => old.checked_sub(*value),
+            Operation::Multiply(value) => old.checked_mul(*value),
+            Operation::Double => old.checked_add(old),
+            Operation::Square => old.checked_mul(old),
+        }
+    }
+
+    // Can't overflow for any i64 `old` and operand.
+    pub fn wide_apply(&self, old: i128) -> i128 {
+        match self {
+            Operation::Add(value) => old + *value as i128,
+            Operation::Subtract(value) => old - *value as i128,
+            Operation::Multiply(value) => old * *value as i128,
+            Operation::Double => old + old,
+            Operation::Square => old * old,
+        }
+    }
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operation::Add(value) => write!(f, "old + {value}"),
+            Operation::Subtract(value) => write!(f, "old - {value}"),
+            Operation::Multiply(value) => write!(f, "old * {value}"),
+            Operation::Double => write!(f, "old + old"),
+            Operation::Square => write!(f, "old * old"),
+        }
+    }
+}
+
+impl FromStr for Operation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |value: &str| {
+            value
+                .parse::<i64>()
+                .map_err(|_| format!("'{value}' is not a valid number"))
+        };
+        let tokens = s.split_whitespace().collect::<Vec<&str>>();
+        match tokens[..] {
+            ["old", "+", "old"] => Ok(Operation::Double),
+            ["old", "*", "old"] => Ok(Operation::Square),
+            ["old", "+", value] | [value, "+", "old"] => Ok(Operation::Add(number(value)?)),
+            ["old", "-", value] => Ok(Operation::Subtract(number(value)?)),
+            ["old", "*", value] | [value, "*", "old"] => Ok(Operation::Multiply(number(value)?)),
+            _ => Err(format!("unsupported operation '{}'", s.trim())),
+        }
+    }
+}
 
-fn default_worry_manager(worry: i64) -> i64 {
-    worry / 3
+// How worry levels are kept in check after each inspection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WorryPolicy {
+    // Worry is divided by 3 (part 1). Overflows are detected and reported.
+    Relief,
+    // Worry is kept modulo the LCM of all test values, which doesn't change
+    // the outcome of any test (part 2). Operations are done on 128 bits so
+    // they can't overflow.
+    Modulo,
 }
 
+// A `WorryPolicy` resolved against the monkeys it applies to.
+#[derive(Clone, Copy)]
+enum WorryManager {
+    Divide(i64),
+    Modulo(i64),
+}
+
+impl WorryManager {
+    fn inspect(&self, operation: &Operation, worry: i64) -> Option<i64> {
+        match self {
+            WorryManager::Divide(divisor) => operation.checked_apply(worry).map(|w| w / divisor),
+            // `rem_euclid` rather than `%`: subtractions can make worry go
+            // negative, and `%` would keep it negative. Tests come out the
+            // same either way, but worry levels stay in [0, modulus), so
+            // equal states compare equal (see `cycle.rs`).
+            WorryManager::Modulo(modulus) => Some(
+                operation
+                    .wide_apply(worry as i128)
+                    .rem_euclid(*modulus as i128) as i64,
+            ),
+        }
+    }
+}
+
+fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Monkey {
-    operation: Box<dyn Fn(i64) -> i64 + 'static>,
+    operation: Operation,
     test_value: i64,
     dest_true: usize,
     dest_false: usize,
-    worry_manager: Box<dyn Fn(i64) -> i64 + 'static>,
     inspected: i64,
 }
 
 impl Monkey {
     pub fn new(
-        operation: Box<dyn Fn(i64) -> i64 + 'static>,
+        operation: Operation,
         test_value: i64,
         dest_true: usize,
         dest_false: usize,
     ) -> Monkey {
         Monkey {
-            operation: operation,
-            test_value: test_value,
-            dest_true: dest_true,
-            dest_false: dest_false,
-            worry_manager: Box::new(default_worry_manager),
+            operation,
+            test_value,
+            dest_true,
+            dest_false,
             inspected: 0,
         }
     }
 
-    pub fn new_worry(
-        operation: Box<dyn Fn(i64) -> i64 + 'static>,
-        test_value: i64,
-        dest_true: usize,
-        dest_false: usize,
-        worry_manager: Box<dyn Fn(i64) -> i64 + 'static>,
-    ) -> Monkey {
-        Monkey {
-            operation: operation,
-            test_value: test_value,
-            dest_true: dest_true,
-            dest_false: dest_false,
-            worry_manager: worry_manager,
-            inspected: 0,
-        }
+    pub fn test_value(&self) -> i64 {
+        self.test_value
+    }
+
+    pub fn inspected(&self) -> i64 {
+        self.inspected
     }
 
-    pub fn play(&mut self, id: usize, queues: &mut Vec<VecDeque<i64>>) {
-        while let Some(mut worry) = queues[id].pop_front() {
-            worry = (self.operation)(worry);
-            worry = (self.worry_manager)(worry);
-            if worry % self.test_value == 0 {
-                queues[self.dest_true].push_front(worry);
+    // Throws are only recorded if `throws` is given.
+    fn play(
+        &mut self,
+        id: usize,
+        queues: &mut [VecDeque<i64>],
+        manager: WorryManager,
+        mut throws: Option<&mut Vec<Throw>>,
+    ) -> Result<(), String> {
+        while let Some(worry) = queues[id].pop_front() {
+            let worry = manager
+                .inspect(&self.operation, worry)
+                .ok_or_else(|| format!("monkey {id}: worry level {worry} overflowed"))?;
+            let dest = if worry % self.test_value == 0 {
+                self.dest_true
             } else {
-                queues[self.dest_false].push_front(worry);
+                self.dest_false
+            };
+            queues[dest].push_back(worry);
+            if let Some(throws) = throws.as_mut() {
+                throws.push(Throw {
+                    from: id,
+                    to: dest,
+                    worry,
+                });
             }
             self.inspected += 1;
         }
+        Ok(())
     }
 }
 
+// The whole state of the game, which can be saved between rounds with serde
+// and reloaded to carry on playing.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MonkeyBusiness {
     monkeys: Vec<Monkey>,
     queues: Vec<VecDeque<i64>>,
+    policy: WorryPolicy,
+    rounds: usize,
+}
+
+impl Default for MonkeyBusiness {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl MonkeyBusiness {
@@ -71,6 +204,8 @@ impl MonkeyBusiness {
         MonkeyBusiness {
             monkeys: Vec::new(),
             queues: Vec::new(),
+            policy: WorryPolicy::Relief,
+            rounds: 0,
         }
     }
 
@@ -79,22 +214,171 @@ impl MonkeyBusiness {
         self.queues.push(items);
     }
 
-    pub fn play(&mut self, rounds: usize) {
+    // Checks what `play` relies on, for states that don't come from
+    // `MonkeyBusiness::parse` (e.g. reloaded with serde): each monkey has a
+    // queue, tests a non-zero value and throws to other monkeys that exist.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.monkeys.is_empty() {
+            return Err("no monkey found".to_string());
+        }
+        if self.queues.len() != self.monkeys.len() {
+            return Err(format!(
+                "{} queues of items for {} monkeys",
+                self.queues.len(),
+                self.monkeys.len()
+            ));
+        }
+        for (id, monkey) in self.monkeys.iter().enumerate() {
+            if monkey.test_value == 0 {
+                return Err(format!("monkey {id} tests for divisibility by 0"));
+            }
+            for dest in [monkey.dest_true, monkey.dest_false] {
+                if dest >= self.monkeys.len() {
+                    return Err(format!("monkey {id} throws to unknown monkey {dest}"));
+                }
+                if dest == id {
+                    return Err(format!("monkey {id} throws to itself"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set_policy(&mut self, policy: WorryPolicy) {
+        self.policy = policy;
+    }
+
+    // Least common multiple of all test values, None if it doesn't fit in an
+    // i64.
+    pub fn modulus(&self) -> Option<i64> {
+        self.monkeys
+            .iter()
+            .try_fold(1i128, |lcm, monkey| {
+                let value = monkey.test_value as i128;
+                let lcm = lcm / gcd(lcm, value) * value.abs();
+                (lcm <= i64::MAX as i128).then_some(lcm)
+            })
+            .map(|lcm| lcm as i64)
+    }
+
+    fn worry_manager(&self) -> Result<WorryManager, String> {
+        match self.policy {
+            WorryPolicy::Relief => Ok(WorryManager::Divide(3)),
+            WorryPolicy::Modulo => self
+                .modulus()
+                .map(WorryManager::Modulo)
+                .ok_or_else(|| "LCM of test values doesn't fit in an i64".to_string()),
+        }
+    }
+
+    // Rounds played so far.
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    pub fn monkeys(&self) -> &[Monkey] {
+        &self.monkeys
+    }
+
+    // Items held by each monkey, in the order they will be inspected.
+    pub fn items(&self) -> &[VecDeque<i64>] {
+        &self.queues
+    }
+
+    pub fn play(&mut self, rounds: usize) -> Result<(), String> {
+        let manager = self.worry_manager()?;
+        for _ in 0..rounds {
+            for i in 0..self.monkeys.len() {
+                self.monkeys[i].play(i, &mut self.queues, manager, None)?;
+            }
+            self.rounds += 1;
+        }
+        Ok(())
+    }
+
+    // Same as `play`, but hands a snapshot of every `every` round (with all
+    // the throws made during that round) to `observer`.
+    pub fn play_observed<F>(
+        &mut self,
+        rounds: usize,
+        every: usize,
+        mut observer: F,
+    ) -> Result<(), String>
+    where
+        F: FnMut(&RoundStats),
+    {
+        let manager = self.worry_manager()?;
+        let mut throws = Vec::new();
         for _ in 0..rounds {
+            let record = (self.rounds + 1).is_multiple_of(every.max(1));
+            let before = self.monkeys.iter().map(|m| m.inspected).collect::<Vec<_>>();
             for i in 0..self.monkeys.len() {
-                self.monkeys[i].play(i, &mut self.queues);
+                let throws = if record { Some(&mut throws) } else { None };
+                self.monkeys[i].play(i, &mut self.queues, manager, throws)?;
+            }
+            self.rounds += 1;
+            if record {
+                observer(&RoundStats::snapshot(
+                    self,
+                    &before,
+                    std::mem::take(&mut throws),
+                ));
             }
         }
+        Ok(())
     }
 
-    pub fn most_active(self) -> (i64, i64) {
+    // Convenience over `play_observed` that keeps all snapshots.
+    pub fn play_with_stats(
+        &mut self,
+        rounds: usize,
+        every: usize,
+    ) -> Result<Vec<RoundStats>, String> {
+        let mut stats = Vec::new();
+        self.play_observed(rounds, every, |round| stats.push(round.clone()))?;
+        Ok(stats)
+    }
+
+    // The `k` monkeys that inspected the most items, most active first, as
+    // (monkey, inspections).
+    pub fn top_active(&self, k: usize) -> Vec<(usize, i64)> {
         let mut activity = self
             .monkeys
             .iter()
             .map(|x| x.inspected)
-            .collect::<Vec<i64>>();
-        let len = activity.len();
-        activity.select_nth_unstable(len - 2);
-        (activity[len - 1], activity[len - 2])
+            .enumerate()
+            .collect::<Vec<_>>();
+        activity.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        activity.truncate(k);
+        activity
+    }
+
+    pub fn most_active(&self) -> (i64, i64) {
+        let top = self.top_active(2);
+        (top[0].1, top[1].1)
+    }
+}
+
+// Prints the current state as puzzle notes, which `MonkeyBusiness::parse` can
+// read back (inspection counts, rounds and policy aside).
+impl Display for MonkeyBusiness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (id, (monkey, items)) in self.monkeys.iter().zip(&self.queues).enumerate() {
+            if id > 0 {
+                writeln!(f)?;
+            }
+            let items = items
+                .iter()
+                .map(|item| format!(" {item}"))
+                .collect::<Vec<_>>()
+                .join(",");
+            writeln!(f, "Monkey {id}:")?;
+            writeln!(f, "  Starting items:{items}")?;
+            writeln!(f, "  Operation: new = {}", monkey.operation)?;
+            writeln!(f, "  Test: divisible by {}", monkey.test_value)?;
+            writeln!(f, "    If true: throw to monkey {}", monkey.dest_true)?;
+            writeln!(f, "    If false: throw to monkey {}", monkey.dest_false)?;
+        }
+        Ok(())
     }
 }
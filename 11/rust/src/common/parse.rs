@@ -0,0 +1,139 @@
+use std::collections::VecDeque;
+
+use super::{Monkey, MonkeyBusiness, Operation};
+
+// Parser for the puzzle's monkey notes, e.g.:
+//
+//   Monkey 0:
+//     Starting items: 79, 98
+//     Operation: new = old * 19
+//     Test: divisible by 23
+//       If true: throw to monkey 2
+//       If false: throw to monkey 3
+//
+// Monkeys are separated by a blank line and must be listed in order. Errors
+// are reported with the line number they were found at.
+
+struct Lines<'a> {
+    lines: std::iter::Peekable<std::iter::Enumerate<std::str::Lines<'a>>>,
+    last: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn new(input: &'a str) -> Lines<'a> {
+        Lines {
+            lines: input.lines().enumerate().peekable(),
+            last: 0,
+        }
+    }
+
+    fn skip_blank(&mut self) {
+        while let Some((_, line)) = self.lines.peek() {
+            if !line.trim().is_empty() {
+                break;
+            }
+            self.lines.next();
+        }
+    }
+
+    fn is_done(&mut self) -> bool {
+        self.skip_blank();
+        self.lines.peek().is_none()
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("line {}: {message}", self.last)
+    }
+
+    // Next line, which must start with `prefix` once trimmed; returns what follows.
+    fn expect(&mut self, prefix: &str) -> Result<&'a str, String> {
+        let (num, line) = self
+            .lines
+            .next()
+            .ok_or_else(|| format!("unexpected end of input, expected '{prefix}'"))?;
+        self.last = num + 1;
+        line.trim()
+            .strip_prefix(prefix)
+            .map(str::trim)
+            .ok_or_else(|| self.error(&format!("expected '{prefix}'")))
+    }
+
+    fn number<T: std::str::FromStr>(&self, text: &str) -> Result<T, String> {
+        text.trim()
+            .parse::<T>()
+            .map_err(|_| self.error(&format!("'{text}' is not a valid number")))
+    }
+}
+
+fn parse_operation(lines: &Lines, text: &str) -> Result<Operation, String> {
+    let expr = text
+        .strip_prefix("new =")
+        .ok_or_else(|| lines.error("operation should start with 'new ='"))?;
+    expr.parse::<Operation>().map_err(|err| lines.error(&err))
+}
+
+fn parse_monkey(lines: &mut Lines, id: usize) -> Result<(VecDeque<i64>, Monkey), String> {
+    let header = lines.expect("Monkey")?;
+    let found = header
+        .strip_suffix(':')
+        .ok_or_else(|| lines.error("expected ':' after monkey number"))?;
+    if lines.number::<usize>(found)? != id {
+        return Err(lines.error(&format!("expected monkey {id}, found monkey {found}")));
+    }
+
+    let items = lines.expect("Starting items:")?;
+    let items = items
+        .split(',')
+        .filter(|item| !item.trim().is_empty())
+        .map(|item| lines.number(item))
+        .collect::<Result<VecDeque<i64>, String>>()?;
+
+    let operation = lines.expect("Operation:")?;
+    let operation = parse_operation(lines, operation)?;
+
+    let test_value = lines.expect("Test: divisible by")?;
+    let test_value = lines.number::<i64>(test_value)?;
+    if test_value == 0 {
+        return Err(lines.error("can't test for divisibility by 0"));
+    }
+
+    let dest_true = lines.expect("If true: throw to monkey")?;
+    let dest_true = lines.number(dest_true)?;
+    let dest_false = lines.expect("If false: throw to monkey")?;
+    let dest_false = lines.number(dest_false)?;
+
+    Ok((
+        items,
+        Monkey::new(operation, test_value, dest_true, dest_false),
+    ))
+}
+
+// Monkeys and their starting items, in the order they are listed. Where they
+// throw items isn't checked here, see `MonkeyBusiness::validate`.
+pub fn parse_monkeys(input: &str) -> Result<Vec<(VecDeque<i64>, Monkey)>, String> {
+    let mut lines = Lines::new(input);
+    let mut monkeys = Vec::new();
+    while !lines.is_done() {
+        monkeys.push(parse_monkey(&mut lines, monkeys.len())?);
+    }
+    Ok(monkeys)
+}
+
+impl MonkeyBusiness {
+    pub fn parse(input: &str) -> Result<MonkeyBusiness, String> {
+        let mut monkey_business = MonkeyBusiness::new();
+        for (items, monkey) in parse_monkeys(input)? {
+            monkey_business.add(items, monkey);
+        }
+        monkey_business.validate()?;
+        Ok(monkey_business)
+    }
+
+    // A state saved as JSON with serde, checked like parsed notes.
+    pub fn from_json(input: &str) -> Result<MonkeyBusiness, String> {
+        let monkey_business: MonkeyBusiness =
+            serde_json::from_str(input).map_err(|err| format!("bad JSON state: {err}"))?;
+        monkey_business.validate()?;
+        Ok(monkey_business)
+    }
+}
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+use super::MonkeyBusiness;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Throw {
+    pub from: usize,
+    pub to: usize,
+    pub worry: i64, // after inspection
+}
+
+// State of the game at the end of a round.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundStats {
+    pub round: usize,          // 1 for the first round
+    pub items: Vec<Vec<i64>>,  // held by each monkey
+    pub inspections: Vec<i64>, // by each monkey during this round
+    pub inspected: Vec<i64>,   // by each monkey since the start
+    pub throws: Vec<Throw>,    // during this round, in order
+}
+
+impl RoundStats {
+    pub(super) fn snapshot(
+        monkey_business: &MonkeyBusiness,
+        before: &[i64],
+        throws: Vec<Throw>,
+    ) -> RoundStats {
+        let inspected = monkey_business
+            .monkeys()
+            .iter()
+            .map(|monkey| monkey.inspected())
+            .collect::<Vec<_>>();
+        RoundStats {
+            round: monkey_business.rounds(),
+            items: monkey_business
+                .items()
+                .iter()
+                .map(|items| items.iter().copied().collect())
+                .collect(),
+            inspections: inspected
+                .iter()
+                .zip(before)
+                .map(|(after, before)| after - before)
+                .collect(),
+            inspected,
+            throws,
+        }
+    }
+
+    // Number of throws from each monkey to each other monkey: `[from][to]`.
+    pub fn throw_matrix(&self) -> Vec<Vec<usize>> {
+        let mut matrix = vec![vec![0; self.items.len()]; self.items.len()];
+        for throw in &self.throws {
+            matrix[throw.from][throw.to] += 1;
+        }
+        matrix
+    }
+}
+
+// One line per monkey and round, easy to chart with any spreadsheet.
+pub fn to_csv(stats: &[RoundStats]) -> String {
+    let mut csv = String::from("round,monkey,held,inspections,inspected,thrown,received\n");
+    for round in stats {
+        let matrix = round.throw_matrix();
+        for monkey in 0..round.items.len() {
+            let thrown: usize = matrix[monkey].iter().sum();
+            let received: usize = matrix.iter().map(|row| row[monkey]).sum();
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{}\n",
+                round.round,
+                monkey,
+                round.items[monkey].len(),
+                round.inspections[monkey],
+                round.inspected[monkey],
+                thrown,
+                received,
+            ));
+        }
+    }
+    csv
+}
+
+// One line per throw.
+pub fn throws_to_csv(stats: &[RoundStats]) -> String {
+    let mut csv = String::from("round,from,to,worry\n");
+    for round in stats {
+        for throw in &round.throws {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                round.round, throw.from, throw.to, throw.worry
+            ));
+        }
+    }
+    csv
+}
+
+pub fn to_json(stats: &[RoundStats]) -> String {
+    serde_json::to_string(stats).unwrap()
+}
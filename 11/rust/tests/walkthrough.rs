@@ -0,0 +1,85 @@
+use monkey::common::{MonkeyBusiness, WorryPolicy};
+
+// Items held by monkeys 0 and 1 after some rounds of the puzzle's example
+// (monkeys 2 and 3 always end up empty handed), in the order they are listed
+// in the walkthrough.
+const WALKTHROUGH: [(usize, [&[i64]; 2]); 8] = [
+    (1, [&[20, 23, 27, 26], &[2080, 25, 167, 207, 401, 1046]]),
+    (2, [&[695, 10, 71, 135, 350], &[43, 49, 58, 55, 362]]),
+    (3, [&[16, 18, 21, 20, 122], &[1468, 22, 150, 286, 739]]),
+    (4, [&[491, 9, 52, 97, 248, 34], &[39, 45, 43, 258]]),
+    (5, [&[15, 17, 16, 88, 1037], &[20, 110, 205, 524, 72]]),
+    (10, [&[91, 16, 20, 98], &[481, 245, 22, 26, 1092, 30]]),
+    (15, [&[83, 44, 8, 184, 9, 20, 26, 102], &[110, 36]]),
+    (20, [&[10, 12, 14, 26, 34], &[245, 93, 53, 199, 115]]),
+];
+
+#[test]
+fn holdings_follow_the_example_walkthrough() {
+    let mut monkey_business = MonkeyBusiness::parse(include_str!("../../example.txt")).unwrap();
+    let stats = monkey_business.play_with_stats(20, 1).unwrap();
+
+    for (round, [first, second]) in WALKTHROUGH {
+        let items = &stats[round - 1].items;
+        assert_eq!(stats[round - 1].round, round);
+        assert_eq!(items[0], first, "monkey 0 after round {round}");
+        assert_eq!(items[1], second, "monkey 1 after round {round}");
+        assert!(items[2].is_empty() && items[3].is_empty());
+    }
+    assert_eq!(stats[19].inspected, [101, 95, 7, 105]);
+}
+
+#[test]
+fn saved_states_are_checked() {
+    let monkey_business = MonkeyBusiness::parse(include_str!("../../example.txt")).unwrap();
+    let json = serde_json::to_string(&monkey_business).unwrap();
+    assert_eq!(MonkeyBusiness::from_json(&json), Ok(monkey_business));
+
+    // Monkey 0 throws to monkey 2 if the test is true
+    for (dest, err) in [
+        ("7", "monkey 0 throws to unknown monkey 7"),
+        ("0", "monkey 0 throws to itself"),
+    ] {
+        let json = json.replacen("\"dest_true\":2", &format!("\"dest_true\":{dest}"), 1);
+        assert_eq!(MonkeyBusiness::from_json(&json), Err(err.to_string()));
+    }
+}
+
+#[test]
+fn fast_forward_plays_like_play() {
+    let mut start = MonkeyBusiness::parse(include_str!("../../example.txt")).unwrap();
+    start.set_policy(WorryPolicy::Modulo);
+    // Prime numbers of rounds can't be multiples of any cycle longer than 1
+    for rounds in [1, 7, 997, 4999, 10000] {
+        let mut played = start.clone();
+        played.play(rounds).unwrap();
+        let mut skipped = start.clone();
+        skipped.fast_forward(rounds).unwrap();
+
+        let inspected = |monkey_business: &MonkeyBusiness| {
+            let monkeys = monkey_business.monkeys().iter();
+            monkeys.map(|monkey| monkey.inspected()).collect::<Vec<_>>()
+        };
+        assert_eq!(inspected(&skipped), inspected(&played), "{rounds} rounds");
+        assert_eq!(skipped.rounds(), rounds);
+        // Items may be queued in another order
+        for (monkey, (a, b)) in skipped.items().iter().zip(played.items()).enumerate() {
+            let (mut a, mut b) = (a.clone(), b.clone());
+            a.make_contiguous().sort();
+            b.make_contiguous().sort();
+            assert_eq!(a, b, "monkey {monkey} after {rounds} rounds");
+        }
+    }
+    start.play(10000).unwrap();
+    assert_eq!(start.most_active(), (52166, 52013));
+}
+
+#[test]
+fn monkeys_throw_to_others() {
+    let notes =
+        include_str!("../../example.txt").replacen("throw to monkey 2", "throw to monkey 0", 1);
+    assert_eq!(
+        MonkeyBusiness::parse(&notes),
+        Err("monkey 0 throws to itself".to_string())
+    );
+}
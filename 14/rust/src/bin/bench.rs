@@ -0,0 +1,76 @@
+use day14::common::*;
+use geo::coord;
+use geo::geometry::{Coord, LineString};
+use shared::random::Lcg;
+use std::env;
+use std::io::{self, Read};
+use std::time::Instant;
+
+// Compares the ways to fill a cave with sand. Reads rock paths on stdin, or
+// generates a cave with the given number of rock paths:
+//
+//   cargo run --release --bin bench 2000
+fn generate(count: usize) -> Vec<LineString<i32>> {
+    let mut lcg = Lcg::new(14);
+    let mut random = |range: i32| lcg.below(range as u64) as i32;
+    let depth = count as i32 / 4 + 20;
+    (0..count)
+        .map(|_| {
+            let mut point = coord! { x: 500 - depth + random(2 * depth), y: 2 + random(depth) };
+            let mut coords: Vec<Coord<i32>> = vec![point];
+            for step in 0..1 + random(4) {
+                match step % 2 {
+                    0 => point.x += random(17) - 8,
+                    _ => point.y += random(6) + 1,
+                }
+                coords.push(point);
+            }
+            LineString::from(coords)
+        })
+        .collect()
+}
+
+fn main() {
+    let paths = match env::args().nth(1) {
+        Some(arg) => generate(
+            arg.parse()
+                .expect("Error 1st positional argument: expect usize"),
+        ),
+        None => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input).unwrap();
+            parse_paths(&input)
+        }
+    };
+    let source = coord! { x: 500, y: 0 };
+
+    for boundary in [Boundary::Abyss, Boundary::Floor] {
+        let mut cave =
+            Cave::new(&paths, vec![source], boundary).unwrap_or_else(|err| panic!("{err}"));
+        let start = Instant::now();
+        while let Outcome::Rest(_) = cave.drop_grain(source) {}
+        println!(
+            "{boundary:?}: naive {} grains in {:?}",
+            cave.grains(),
+            start.elapsed()
+        );
+
+        let mut cave =
+            Cave::new(&paths, vec![source], boundary).unwrap_or_else(|err| panic!("{err}"));
+        let start = Instant::now();
+        let grains = cave.fill();
+        println!(
+            "{boundary:?}: memoised {grains} grains in {:?}",
+            start.elapsed()
+        );
+
+        let cave = Cave::new(&paths, vec![source], boundary).unwrap_or_else(|err| panic!("{err}"));
+        let start = Instant::now();
+        if let Some(grains) = cave.flood_count() {
+            println!(
+                "{boundary:?}: flood {grains} grains in {:?}",
+                start.elapsed()
+            );
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use day14::common::{render, *};
+use geo::coord;
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+// Fills the cave from 500,0 and prints it. Optionally writes a frame every N
+// grains as numbered PPM files, e.g. to make an animation of part 2:
+//
+//   render floor 100 frames < ../example.txt
+//   ffmpeg -i frames/%05d.ppm cave.gif
+fn main() {
+    let mut args = env::args().skip(1);
+    let boundary = match args.next().as_deref() {
+        Some("abyss") | None => Boundary::Abyss,
+        Some("floor") => Boundary::Floor,
+        Some("walls") => Boundary::Walls,
+        _ => panic!("Error 1st positional argument: expect 'abyss', 'floor' or 'walls'"),
+    };
+    let every: usize = args
+        .next()
+        .map(|arg| {
+            arg.parse()
+                .expect("Error 2nd positional argument: expect usize")
+        })
+        .unwrap_or(0);
+    let dir = args.next().unwrap_or_else(|| "frames".to_string());
+
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input).unwrap();
+    let paths = parse_paths(&input);
+    let sources = vec![coord! { x: 500, y: 0 }];
+
+    let mut cave =
+        Cave::new(&paths, sources.clone(), boundary).unwrap_or_else(|err| panic!("{err}"));
+    if every > 0 {
+        // Frames all show the area of the filled cave
+        let area = {
+            let mut filled =
+                Cave::new(&paths, sources.clone(), boundary).unwrap_or_else(|err| panic!("{err}"));
+            filled.fill();
+            filled.used_area()
+        };
+        let scale = std::cmp::max(1, 800 / area.width().max(area.height()) as usize);
+        fs::create_dir_all(&dir).unwrap();
+        let mut frame = 0;
+        cave.fill_observed(every, |cave, falling| {
+            frame += 1;
+            let path = Path::new(&dir).join(format!("{frame:05}.ppm"));
+            render::write_ppm(cave, area, falling, scale, &path).unwrap();
+        });
+        eprintln!("Wrote {frame} frames in {dir}");
+    } else {
+        cave.fill();
+    }
+    print!("{cave}");
+    println!("Sand grains dropped: {}", cave.grains());
+}
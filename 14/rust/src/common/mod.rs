@@ -0,0 +1,374 @@
+pub mod render;
+
+use geo::geometry::{Coord, LineString, Rect};
+use geo::{coord, BoundingRect};
+use ndarray::{s, Array2};
+use std::cmp::{max, min};
+use std::fmt::Display;
+
+// Scanned rock paths, one per line, e.g. "498,4 -> 498,6 -> 496,6" (or with
+// just "-" as a separator).
+pub fn parse_paths(input: &str) -> Vec<LineString<i32>> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.replace("->", "-")
+                .split('-')
+                .map(|str_point| {
+                    let mut iter = str_point.trim().split(',');
+                    coord! {
+                        x: iter.next().unwrap().parse::<i32>().unwrap(),
+                        y: iter.next().unwrap().parse::<i32>().unwrap(),
+                    }
+                })
+                .collect::<LineString<i32>>()
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Tile {
+    Air,
+    Rock,
+    Sand,
+}
+
+// What lies beyond the scanned rocks.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Boundary {
+    // Sand falling below the lowest rock is lost (part 1).
+    Abyss,
+    // An infinite floor 2 units below the lowest rock (part 2).
+    Floor,
+    // Same floor, with walls 1 unit left and right of the outermost rocks or
+    // sources, so the cave doesn't grow.
+    Walls,
+}
+
+// Where a grain of sand ended up.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Outcome {
+    Rest(Coord<i32>),
+    Abyss,
+    Blocked, // the source is covered by sand already
+}
+
+// One move of a falling grain of sand.
+enum Step {
+    Fall(Coord<i32>),
+    Rest,
+    Abyss,
+}
+
+pub struct Cave {
+    // Indexed by [x, y] relative to `origin`; x bounds grow as needed
+    map: Array2<Tile>,
+    origin: Coord<i32>,
+    sources: Vec<Coord<i32>>,
+    boundary: Boundary,
+    lowest_rock: i32,
+    walls: (i32, i32),
+    grains: usize,
+}
+
+impl Cave {
+    // Fails on diagonal rock paths, and on sources sand couldn't fall from:
+    // at or below the floor, when there's one.
+    pub fn new(
+        paths: &[LineString<i32>],
+        sources: Vec<Coord<i32>>,
+        boundary: Boundary,
+    ) -> Result<Cave, String> {
+        if sources.is_empty() {
+            return Err("a cave needs a source of sand".to_string());
+        }
+        let mut bounds = (sources[0], sources[0]);
+        for coord in sources
+            .iter()
+            .copied()
+            .chain(paths.iter().flat_map(|path| path.coords().copied()))
+        {
+            bounds.0 = coord! { x: min(bounds.0.x, coord.x), y: min(bounds.0.y, coord.y) };
+            bounds.1 = coord! { x: max(bounds.1.x, coord.x), y: max(bounds.1.y, coord.y) };
+        }
+        let lowest_rock = paths
+            .iter()
+            .flat_map(|path| path.coords())
+            .map(|coord| coord.y)
+            .max()
+            .unwrap_or(bounds.1.y);
+
+        // Rows go down to the floor (included) whatever the boundary.
+        let origin = coord! { x: bounds.0.x - 1, y: bounds.0.y };
+        let size = [
+            (bounds.1.x - bounds.0.x + 3) as usize,
+            (lowest_rock + 3 - origin.y) as usize,
+        ];
+        let mut cave = Cave {
+            map: Array2::from_elem(size, Tile::Air),
+            origin,
+            sources,
+            boundary,
+            lowest_rock,
+            walls: (bounds.0.x - 1, bounds.1.x + 1),
+            grains: 0,
+        };
+        if boundary != Boundary::Abyss {
+            if let Some(source) = cave.sources.iter().find(|source| source.y >= cave.floor()) {
+                return Err(format!(
+                    "source {},{} is not above the floor (y = {})",
+                    source.x,
+                    source.y,
+                    cave.floor()
+                ));
+            }
+        }
+
+        for path in paths {
+            for line in path.lines() {
+                let rect = line.bounding_rect();
+                if rect.height() != 0 && rect.width() != 0 {
+                    return Err(format!(
+                        "diagonal rock path from {},{} to {},{}",
+                        line.start.x, line.start.y, line.end.x, line.end.y
+                    ));
+                }
+                for x in rect.min().x..(rect.max().x + 1) {
+                    for y in rect.min().y..(rect.max().y + 1) {
+                        cave.set(coord! { x: x, y: y }, Tile::Rock);
+                    }
+                }
+            }
+        }
+        Ok(cave)
+    }
+
+    pub fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    pub fn sources(&self) -> &[Coord<i32>] {
+        &self.sources
+    }
+
+    // Grains of sand at rest.
+    pub fn grains(&self) -> usize {
+        self.grains
+    }
+
+    fn floor(&self) -> i32 {
+        self.lowest_rock + 2
+    }
+
+    // Smallest area holding all rocks, sand and sources, plus the floor and
+    // walls when there are some.
+    pub fn used_area(&self) -> Rect<i32> {
+        let mut coords = self.sources.clone();
+        for ((x, y), tile) in self.map.indexed_iter() {
+            if *tile != Tile::Air {
+                coords.push(coord! { x: x as i32 + self.origin.x, y: y as i32 + self.origin.y });
+            }
+        }
+        match self.boundary {
+            Boundary::Abyss => (),
+            Boundary::Floor => coords.push(coord! { x: self.sources[0].x, y: self.floor() }),
+            Boundary::Walls => {
+                coords.push(coord! { x: self.walls.0, y: self.floor() });
+                coords.push(coord! { x: self.walls.1, y: self.floor() });
+            }
+        }
+        coords
+            .iter()
+            .fold(Rect::new(coords[0], coords[0]), |rect, coord| {
+                Rect::new(
+                    coord! { x: min(rect.min().x, coord.x), y: min(rect.min().y, coord.y) },
+                    coord! { x: max(rect.max().x, coord.x), y: max(rect.max().y, coord.y) },
+                )
+            })
+    }
+
+    // Anything outside the map is air, save for the floor and walls (and
+    // whatever is under the floor).
+    pub fn get(&self, coord: Coord<i32>) -> Tile {
+        match self.boundary {
+            Boundary::Abyss => (),
+            Boundary::Floor if coord.y >= self.floor() => return Tile::Rock,
+            Boundary::Walls
+                if coord.y >= self.floor()
+                    || coord.x <= self.walls.0
+                    || coord.x >= self.walls.1 =>
+            {
+                return Tile::Rock
+            }
+            _ => (),
+        }
+        let (x, y) = (coord.x - self.origin.x, coord.y - self.origin.y);
+        if x < 0 || y < 0 {
+            return Tile::Air;
+        }
+        *self.map.get([x as usize, y as usize]).unwrap_or(&Tile::Air)
+    }
+
+    // Rows are fixed: sand only comes to rest above the floor, or above the
+    // lowest rock with the abyss below.
+    fn set(&mut self, coord: Coord<i32>, tile: Tile) {
+        let x = coord.x - self.origin.x;
+        if x < 0 || x as usize >= self.map.nrows() {
+            self.grow(coord.x);
+        }
+        let (x, y) = (coord.x - self.origin.x, coord.y - self.origin.y);
+        self.map[[x as usize, y as usize]] = tile;
+    }
+
+    // Make room for column `x`, doubling the width to keep reallocations rare.
+    fn grow(&mut self, x: i32) {
+        let (rows, cols) = self.map.dim();
+        let margin = max(rows, 1) as i32;
+        let (left, right) = if x < self.origin.x {
+            (self.origin.x - x + margin, 0)
+        } else {
+            (0, x - self.origin.x - rows as i32 + 1 + margin)
+        };
+        let mut map = Array2::from_elem([rows + left as usize + right as usize, cols], Tile::Air);
+        map.slice_mut(s![left as usize..left as usize + rows, ..])
+            .assign(&self.map);
+        self.map = map;
+        self.origin.x -= left;
+    }
+
+    // Where a grain of sand at `sand` goes next: down first, then down and to
+    // the left, then down and to the right. Always ends, as there's rock all
+    // along the floor, or the abyss below the lowest rock.
+    fn step(&self, sand: Coord<i32>) -> Step {
+        if self.boundary == Boundary::Abyss && sand.y >= self.lowest_rock {
+            return Step::Abyss;
+        }
+        [0, -1, 1]
+            .into_iter()
+            .map(|dx| coord! { x: sand.x + dx, y: sand.y + 1 })
+            .find(|next| self.get(*next) == Tile::Air)
+            .map_or(Step::Rest, Step::Fall)
+    }
+
+    fn rest(&mut self, sand: Coord<i32>) -> Outcome {
+        self.set(sand, Tile::Sand);
+        self.grains += 1;
+        Outcome::Rest(sand)
+    }
+
+    // Drop one grain of sand from `source` and let it come to rest.
+    pub fn drop_grain(&mut self, source: Coord<i32>) -> Outcome {
+        if self.get(source) != Tile::Air {
+            return Outcome::Blocked;
+        }
+        let mut sand = source;
+        loop {
+            match self.step(sand) {
+                Step::Fall(next) => sand = next,
+                Step::Rest => return self.rest(sand),
+                Step::Abyss => return Outcome::Abyss,
+            }
+        }
+    }
+
+    // Same as `drop_grain`, but the fall resumes from `path`: the positions
+    // the previous grain from this source went through. Only the last of them
+    // got filled, so the new grain would follow the same path up to there.
+    // Sand from other sources may have filled more of it in the meantime.
+    pub fn drop_grain_along(&mut self, source: Coord<i32>, path: &mut Vec<Coord<i32>>) -> Outcome {
+        while let Some(last) = path.last() {
+            if self.get(*last) == Tile::Air {
+                break;
+            }
+            path.pop();
+        }
+        if path.is_empty() {
+            if self.get(source) != Tile::Air {
+                return Outcome::Blocked;
+            }
+            path.push(source);
+        }
+        loop {
+            let sand = *path.last().unwrap();
+            match self.step(sand) {
+                Step::Fall(next) => path.push(next),
+                Step::Rest => {
+                    path.pop();
+                    return self.rest(sand);
+                }
+                Step::Abyss => return Outcome::Abyss,
+            }
+        }
+    }
+
+    // Drop sand from each source in turn; a source stops once its sand falls
+    // into the abyss or it is covered. Returns the grains of sand at rest.
+    pub fn fill(&mut self) -> usize {
+        self.fill_observed(0, |_, _| {})
+    }
+
+    // Same as `fill`, but hands the cave to `observer` every `every` grains
+    // at rest (never if 0), along with where the next grain from the same
+    // source will resume its fall.
+    pub fn fill_observed<F>(&mut self, every: usize, mut observer: F) -> usize
+    where
+        F: FnMut(&Cave, Option<Coord<i32>>),
+    {
+        let mut paths = vec![Vec::new(); self.sources.len()];
+        let mut active = vec![true; self.sources.len()];
+        while active.iter().any(|active| *active) {
+            for (i, source) in self.sources.clone().into_iter().enumerate() {
+                if !active[i] {
+                    continue;
+                }
+                match self.drop_grain_along(source, &mut paths[i]) {
+                    Outcome::Rest(_) => {
+                        if every != 0 && self.grains.is_multiple_of(every) {
+                            observer(self, paths[i].last().copied());
+                        }
+                    }
+                    _ => active[i] = false,
+                }
+            }
+        }
+        self.grains
+    }
+
+    // With a floor, the cave ends up filled with sand everywhere sand can get
+    // to: any position below a source and reachable by going down, or down and
+    // to the left or right, through air. So rather than dropping grains, we
+    // flood the cave row by row, from the sources down to the floor. That's no
+    // closed form (rocks shadow the rows under them in too many ways), but
+    // each position is looked at once. Returns what `fill` would, None if
+    // there's no floor.
+    pub fn flood_count(&self) -> Option<usize> {
+        if self.boundary == Boundary::Abyss {
+            return None;
+        }
+        let top = self.sources.iter().map(|source| source.y).min().unwrap();
+        let left = self.sources.iter().map(|source| source.x).min().unwrap() - (self.floor() - top);
+        let right =
+            self.sources.iter().map(|source| source.x).max().unwrap() + (self.floor() - top);
+        let mut count = 0;
+        let mut row = vec![false; (right - left + 1) as usize];
+        for y in top..self.floor() {
+            let mut next = vec![false; row.len()];
+            for (i, next) in next.iter_mut().enumerate() {
+                let x = left + i as i32;
+                let from_above = (i.saturating_sub(1)..min(i + 2, row.len())).any(|j| row[j]);
+                let source = self.sources.contains(&coord! { x: x, y: y });
+                *next = (from_above || source) && self.get(coord! { x: x, y: y }) != Tile::Rock;
+            }
+            count += next.iter().filter(|reached| **reached).count();
+            row = next;
+        }
+        Some(count)
+    }
+}
+
+impl Display for Cave {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", render::to_ascii(self, self.used_area(), None))
+    }
+}
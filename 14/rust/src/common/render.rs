@@ -0,0 +1,90 @@
+use geo::coord;
+use geo::geometry::{Coord, Rect};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use super::{Cave, Tile};
+
+// What to draw at each position of `area` (bounds included), row by row.
+enum Pixel {
+    Air,
+    Rock,
+    Sand,
+    Source,
+    Falling,
+}
+
+fn pixels(cave: &Cave, area: Rect<i32>, falling: Option<Coord<i32>>) -> Vec<Vec<Pixel>> {
+    (area.min().y..=area.max().y)
+        .map(|y| {
+            (area.min().x..=area.max().x)
+                .map(|x| {
+                    let coord = coord! { x: x, y: y };
+                    if falling == Some(coord) {
+                        return Pixel::Falling;
+                    }
+                    match cave.get(coord) {
+                        Tile::Rock => Pixel::Rock,
+                        Tile::Sand => Pixel::Sand,
+                        Tile::Air if cave.sources().contains(&coord) => Pixel::Source,
+                        Tile::Air => Pixel::Air,
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Rocks are '#', sand at rest 'o', sources '+' and the falling grain '~', as
+// in the puzzle description.
+pub fn to_ascii(cave: &Cave, area: Rect<i32>, falling: Option<Coord<i32>>) -> String {
+    let mut ascii = String::new();
+    for row in pixels(cave, area, falling) {
+        for pixel in row {
+            ascii.push(match pixel {
+                Pixel::Air => '.',
+                Pixel::Rock => '#',
+                Pixel::Sand => 'o',
+                Pixel::Source => '+',
+                Pixel::Falling => '~',
+            });
+        }
+        ascii.push('\n');
+    }
+    ascii
+}
+
+// Binary PPM image of `area`, each position drawn as a `scale` by `scale`
+// square. Any image tool (or ffmpeg, for a sequence of them) can read these.
+pub fn write_ppm(
+    cave: &Cave,
+    area: Rect<i32>,
+    falling: Option<Coord<i32>>,
+    scale: usize,
+    path: &Path,
+) -> io::Result<()> {
+    let pixels = pixels(cave, area, falling);
+    let (width, height) = (pixels[0].len() * scale, pixels.len() * scale);
+    let mut out = BufWriter::new(File::create(path)?);
+    write!(out, "P6\n{width} {height}\n255\n")?;
+    for row in pixels {
+        let line = row
+            .iter()
+            .flat_map(|pixel| {
+                let rgb: [u8; 3] = match pixel {
+                    Pixel::Air => [24, 20, 32],
+                    Pixel::Rock => [110, 106, 100],
+                    Pixel::Sand => [230, 196, 110],
+                    Pixel::Source => [220, 40, 40],
+                    Pixel::Falling => [255, 140, 0],
+                };
+                std::iter::repeat_n(rgb, scale).flatten()
+            })
+            .collect::<Vec<u8>>();
+        for _ in 0..scale {
+            out.write_all(&line)?;
+        }
+    }
+    out.flush()
+}
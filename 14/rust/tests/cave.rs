@@ -0,0 +1,47 @@
+use day14::common::*;
+use geo::coord;
+
+fn example(sources: &[(i32, i32)], boundary: Boundary) -> Result<Cave, String> {
+    let paths = parse_paths(include_str!("../../example.txt"));
+    let sources = sources.iter().map(|&(x, y)| coord! { x: x, y: y });
+    Cave::new(&paths, sources.collect(), boundary)
+}
+
+#[test]
+fn example_fills() {
+    for (boundary, grains) in [
+        (Boundary::Abyss, 24),
+        (Boundary::Floor, 93),
+        (Boundary::Walls, 47),
+    ] {
+        let mut cave = example(&[(500, 0)], boundary).unwrap();
+        assert_eq!(cave.fill(), grains, "{boundary:?}");
+
+        // Same sand, grain by grain from the source
+        let mut naive = example(&[(500, 0)], boundary).unwrap();
+        while let Outcome::Rest(_) = naive.drop_grain(coord! { x: 500, y: 0 }) {}
+        assert_eq!(naive.to_string(), cave.to_string(), "{boundary:?}");
+    }
+}
+
+#[test]
+fn sources_below_the_floor() {
+    // The lowest rock is at y = 9, so the floor is at y = 11
+    for boundary in [Boundary::Floor, Boundary::Walls] {
+        for y in [11, 20] {
+            assert_eq!(
+                example(&[(500, 0), (500, y)], boundary).err(),
+                Some(format!("source 500,{y} is not above the floor (y = 11)"))
+            );
+        }
+        let mut cave = example(&[(500, 10)], boundary).unwrap();
+        assert_eq!(cave.flood_count(), Some(1));
+        assert_eq!(cave.fill(), 1);
+        assert_eq!(cave.get(coord! { x: 500, y: 20 }), Tile::Rock);
+        assert_eq!(cave.drop_grain(coord! { x: 500, y: 20 }), Outcome::Blocked);
+    }
+
+    // With the abyss, sand from there is lost right away
+    let mut cave = example(&[(500, 20)], Boundary::Abyss).unwrap();
+    assert_eq!(cave.fill(), 0);
+}
@@ -0,0 +1,83 @@
+use day15::common::coverage::CoverageMap;
+use day15::common::search::*;
+use day15::common::*;
+use geo::coord;
+use geo::geometry::{Coord, Rect};
+use std::env;
+use std::time::Instant;
+
+// Compares the ways to find uncovered positions in the search square, e.g.
+//
+//   cargo run --release --bin bench 4000000 < ../processed.txt
+//
+// The row scans only find the first row with gaps: the other results are
+// checked against it on that row.
+fn main() {
+    let size = env::args()
+        .nth(1)
+        .expect("Missing integer argument: search area size")
+        .parse::<i32>()
+        .expect("Malformed integer argument: search area size");
+    let sensors = read_sensors();
+
+    let start = Instant::now();
+    let reference = scan_rows(&sensors, size, |_| {});
+    println!(
+        "scan: {} position(s) in {:?}",
+        reference.len(),
+        start.elapsed()
+    );
+    let on_row = |positions: &[Coord<i32>]| {
+        let row = reference.first().map(|first| first.y);
+        positions
+            .iter()
+            .filter(|position| Some(position.y) == row)
+            .copied()
+            .collect::<Vec<_>>()
+    };
+    let check = |name: &str, positions: &[Coord<i32>]| {
+        if on_row(positions) != reference {
+            println!("{name}: MISMATCH with scan");
+        }
+    };
+
+    let start = Instant::now();
+    let found = par_scan_rows(&sensors, size, |_| {});
+    println!(
+        "par-scan: {} position(s) in {:?}",
+        found.len(),
+        start.elapsed()
+    );
+    check("par-scan", &found);
+
+    let start = Instant::now();
+    let found = search_edges(&sensors, size);
+    println!(
+        "edges: {} position(s) in {:?}",
+        found.len(),
+        start.elapsed()
+    );
+    check("edges", &found);
+
+    let start = Instant::now();
+    let map = CoverageMap::new(&sensors);
+    let area = Rect::new(coord! { x: 0, y: 0 }, coord! { x: size + 1, y: size + 1 });
+    let regions = map.uncovered(area);
+    println!(
+        "coverage: {} region(s), {} position(s) in {:?}",
+        regions.len(),
+        regions.iter().map(|region| region.size).sum::<i64>(),
+        start.elapsed()
+    );
+    let mut found = reference
+        .first()
+        .map(|first| {
+            (0..=size)
+                .map(|x| coord! { x: x, y: first.y })
+                .filter(|position| !map.is_covered(*position))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    found.sort_unstable_by_key(|position| position.x);
+    check("coverage", &found);
+}
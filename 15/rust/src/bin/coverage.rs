@@ -0,0 +1,50 @@
+use day15::common::coverage::CoverageMap;
+use day15::common::*;
+use geo::coord;
+use geo::geometry::Rect;
+use std::env;
+use std::path::Path;
+
+fn main() {
+    let size = env::args()
+        .nth(1)
+        .expect("Missing integer argument: search area size")
+        .parse::<i32>()
+        .expect("Malformed integer argument: search area size");
+    // Optional: where to write a PPM image of the search area, and its width
+    let image = env::args().nth(2);
+    let width = env::args()
+        .nth(3)
+        .map(|width| {
+            width
+                .parse::<usize>()
+                .expect("Malformed integer argument: image width")
+        })
+        .unwrap_or(400);
+
+    let sensors = read_sensors();
+    let map = CoverageMap::new(&sensors);
+    let area = Rect::new(coord! { x: 0, y: 0 }, coord! { x: size + 1, y: size + 1 });
+
+    println!("Covered positions: {}", map.area());
+    println!(
+        "Covered positions in search area: {}",
+        map.area_within(area)
+    );
+    for region in map.uncovered(area) {
+        let (min, max) = (region.bounds.min(), region.bounds.max());
+        println!(
+            "Uncovered: {} position(s) within x={}..{}, y={}..{}",
+            region.size,
+            min.x,
+            max.x - 1,
+            min.y,
+            max.y - 1,
+        );
+    }
+
+    if let Some(image) = image {
+        map.write_ppm(area, width, Path::new(&image))
+            .unwrap_or_else(|err| panic!("{image}: {err}"));
+    }
+}
@@ -0,0 +1,268 @@
+use geo::coord;
+use geo::geometry::{Coord, Rect};
+use std::cmp::{max, min};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use super::interval::IntervalSet;
+use super::Sensor;
+
+// Turning the plane 45 degrees with `u = x + y` and `v = x - y` turns the
+// diamond covered by a sensor into an axis-aligned box:
+//
+//   |x - sx| + |y - sy| <= r  <=>  |u - su| <= r and |v - sv| <= r
+//
+// The union of these boxes is easy to compute with a sweep over `u`: between
+// two consecutive box edges, the covered `v` form the same `IntervalSet`. The
+// only catch is that (u, v) only maps back to a position when u and v have the
+// same parity, so counts must skip every other (u, v).
+//
+// All areas here are half-open, like `Sensor::bounding_box`: `min()` is
+// included, `max()` is not.
+
+// Number of n in [start, end) with n % 2 == parity.
+fn count_parity(start: i32, end: i32, parity: i32) -> i64 {
+    let below = |n: i32| (n as i64 - parity as i64 + 1).div_euclid(2);
+    max(0, below(end) - below(start))
+}
+
+// A box [u.0, u.1) x [v.0, v.1) in (u, v) coordinates: a rectangle turned 45
+// degrees in (x, y) coordinates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RotatedRect {
+    pub u: (i32, i32),
+    pub v: (i32, i32),
+}
+
+impl RotatedRect {
+    pub fn of_sensor(sensor: &Sensor) -> RotatedRect {
+        let (x, y) = sensor.location.x_y();
+        let r = sensor.coverage_radius();
+        RotatedRect {
+            u: (x + y - r, x + y + r + 1),
+            v: (x - y - r, x - y + r + 1),
+        }
+    }
+
+    // For each u of the box, the range of v [start, end) also within `area`.
+    pub fn rows_within(&self, area: Rect<i32>) -> impl Iterator<Item = (i32, i32, i32)> + '_ {
+        let (x0, x1) = (area.min().x, area.max().x - 1);
+        let (y0, y1) = (area.min().y, area.max().y - 1);
+        (max(self.u.0, x0 + y0)..min(self.u.1, x1 + y1 + 1)).map(move |u| {
+            // x = (u + v) / 2 in [x0, x1] and y = (u - v) / 2 in [y0, y1]
+            let start = max(self.v.0, max(2 * x0 - u, u - 2 * y1));
+            let end = min(self.v.1, min(2 * x1 - u, u - 2 * y0) + 1);
+            (u, start, end)
+        })
+    }
+
+    // Positions in the box, and within `area`, as (count, bounds).
+    fn positions_within(&self, area: Rect<i32>) -> (i64, Option<Rect<i32>>) {
+        let mut count = 0;
+        let mut bounds: Option<(Coord<i32>, Coord<i32>)> = None;
+        for (u, start, end) in self.rows_within(area) {
+            // First and last v with the parity of u
+            let first = start + (start - u).rem_euclid(2);
+            let last = end - 1 - (end - 1 - u).rem_euclid(2);
+            if first > last {
+                continue;
+            }
+            count += count_parity(start, end, u.rem_euclid(2));
+            let low = coord! { x: (u + first) / 2, y: (u - last) / 2 };
+            let high = coord! { x: (u + last) / 2, y: (u - first) / 2 };
+            bounds = Some(match bounds {
+                None => (low, high),
+                Some((a, b)) => (
+                    coord! { x: min(a.x, low.x), y: min(a.y, low.y) },
+                    coord! { x: max(b.x, high.x), y: max(b.y, high.y) },
+                ),
+            });
+        }
+        let bounds = bounds.map(|(a, b)| Rect::new(a, b + coord! { x: 1, y: 1 }));
+        (count, bounds)
+    }
+}
+
+// An uncovered region: a rotated rectangle clipped to the area it was looked
+// for in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Region {
+    pub rect: RotatedRect,
+    pub bounds: Rect<i32>, // of its positions, in (x, y) coordinates
+    pub size: i64,         // number of positions
+}
+
+pub struct CoverageMap {
+    // Slabs [start, end) of u, with the v covered there
+    slabs: Vec<(i32, i32, IntervalSet)>,
+}
+
+impl CoverageMap {
+    pub fn new(sensors: &[Sensor]) -> CoverageMap {
+        let rects = sensors
+            .iter()
+            .map(RotatedRect::of_sensor)
+            .collect::<Vec<_>>();
+        let mut edges = rects
+            .iter()
+            .flat_map(|rect| [rect.u.0, rect.u.1])
+            .collect::<Vec<_>>();
+        edges.sort_unstable();
+        edges.dedup();
+        let slabs = edges
+            .windows(2)
+            .map(|edge| {
+                let covered = rects
+                    .iter()
+                    .filter(|rect| rect.u.0 <= edge[0] && edge[1] <= rect.u.1)
+                    .map(|rect| rect.v)
+                    .collect::<IntervalSet>();
+                (edge[0], edge[1], covered)
+            })
+            .collect();
+        CoverageMap { slabs }
+    }
+
+    pub fn is_covered(&self, point: Coord<i32>) -> bool {
+        let (u, v) = (point.x + point.y, point.x - point.y);
+        let i = self.slabs.partition_point(|&(_, end, _)| end <= u);
+        i < self.slabs.len() && self.slabs[i].0 <= u && self.slabs[i].2.contains(v)
+    }
+
+    // Number of positions covered by at least one sensor.
+    pub fn area(&self) -> i64 {
+        self.slabs
+            .iter()
+            .map(|(start, end, covered)| {
+                let (even, odd) =
+                    covered
+                        .intervals()
+                        .iter()
+                        .fold((0, 0), |(even, odd), &(a, b)| {
+                            (even + count_parity(a, b, 0), odd + count_parity(a, b, 1))
+                        });
+                count_parity(*start, *end, 0) * even + count_parity(*start, *end, 1) * odd
+            })
+            .sum()
+    }
+
+    // Uncovered regions within `area`, as rotated rectangles that don't
+    // overlap. A region that isn't a rotated rectangle (e.g. clipped by the
+    // sides of `area`) comes in several pieces.
+    pub fn uncovered(&self, area: Rect<i32>) -> Vec<Region> {
+        let (x0, x1) = (area.min().x, area.max().x - 1);
+        let (y0, y1) = (area.min().y, area.max().y - 1);
+        let (u_start, u_end) = (x0 + y0, x1 + y1 + 1);
+        let (v_start, v_end) = (x0 - y1, x1 - y0 + 1);
+
+        // Pieces of u over which coverage doesn't change, with what's covered
+        let mut pieces = Vec::new();
+        let mut u = u_start;
+        for (start, end, covered) in &self.slabs {
+            if *end <= u || u_end <= *start {
+                continue;
+            }
+            if u < *start {
+                pieces.push((u, *start, None));
+            }
+            pieces.push((max(u, *start), min(u_end, *end), Some(covered)));
+            u = min(u_end, *end);
+        }
+        if u < u_end {
+            pieces.push((u, u_end, None));
+        }
+
+        // Gaps that continue from one piece to the next make one rectangle
+        let mut open: Vec<RotatedRect> = Vec::new();
+        let mut rects = Vec::new();
+        for (start, end, covered) in pieces {
+            let gaps = match covered {
+                Some(covered) => covered.gaps(v_start, v_end),
+                None => IntervalSet::from_interval(v_start, v_end),
+            };
+            let mut next = Vec::new();
+            for &gap in gaps.intervals() {
+                match open
+                    .iter()
+                    .position(|rect| rect.v == gap && rect.u.1 == start)
+                {
+                    Some(i) => next.push(RotatedRect {
+                        u: (open[i].u.0, end),
+                        v: gap,
+                    }),
+                    None => next.push(RotatedRect {
+                        u: (start, end),
+                        v: gap,
+                    }),
+                }
+            }
+            rects.extend(
+                open.into_iter()
+                    .filter(|rect| !next.iter().any(|n| n.u.0 == rect.u.0 && n.v == rect.v)),
+            );
+            open = next;
+        }
+        rects.extend(open);
+
+        rects
+            .into_iter()
+            .filter_map(|rect| {
+                let (size, bounds) = rect.positions_within(area);
+                bounds.map(|bounds| Region { rect, bounds, size })
+            })
+            .collect()
+    }
+
+    // Number of positions covered within `area`.
+    pub fn area_within(&self, area: Rect<i32>) -> i64 {
+        let total = area.width() as i64 * area.height() as i64;
+        total
+            - self
+                .uncovered(area)
+                .iter()
+                .map(|region| region.size)
+                .sum::<i64>()
+    }
+
+    // Binary PPM image of `area` scaled down to `width` pixels across. Each
+    // pixel shows whether its center is covered; small uncovered regions,
+    // which could fall between pixel centers, are drawn on top in red.
+    pub fn write_ppm(&self, area: Rect<i32>, width: usize, path: &Path) -> io::Result<()> {
+        let height = max(1, width * area.height() as usize / area.width() as usize);
+        let scale = (
+            area.width() as f64 / width as f64,
+            area.height() as f64 / height as f64,
+        );
+        let mut pixels = vec![[24u8, 20, 32]; width * height];
+        for (j, row) in pixels.chunks_mut(width).enumerate() {
+            for (i, pixel) in row.iter_mut().enumerate() {
+                let point = coord! {
+                    x: area.min().x + ((i as f64 + 0.5) * scale.0) as i32,
+                    y: area.min().y + ((j as f64 + 0.5) * scale.1) as i32,
+                };
+                if self.is_covered(point) {
+                    *pixel = [90, 140, 200];
+                }
+            }
+        }
+        for region in self.uncovered(area) {
+            if region.size > 10000 {
+                continue;
+            }
+            for (u, start, end) in region.rect.rows_within(area) {
+                for v in (start..end).filter(|v| (v - u) % 2 == 0) {
+                    let (x, y) = ((u + v) / 2, (u - v) / 2);
+                    let i = min(width - 1, ((x - area.min().x) as f64 / scale.0) as usize);
+                    let j = min(height - 1, ((y - area.min().y) as f64 / scale.1) as usize);
+                    pixels[j * width + i] = [230, 40, 40];
+                }
+            }
+        }
+
+        let mut out = BufWriter::new(File::create(path)?);
+        write!(out, "P6\n{width} {height}\n255\n")?;
+        out.write_all(&pixels.concat())?;
+        out.flush()
+    }
+}
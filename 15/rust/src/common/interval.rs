@@ -0,0 +1,116 @@
+use std::cmp::{max, min};
+
+// A set of integers stored as sorted, disjoint half-open intervals [x, y),
+// where all v: x <= v < y belong to the interval. Touching intervals are
+// merged, so the representation of a given set is unique.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    intervals: Vec<(i32, i32)>,
+}
+
+impl IntervalSet {
+    pub fn new() -> IntervalSet {
+        IntervalSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn from_interval(start: i32, end: i32) -> IntervalSet {
+        let mut set = IntervalSet::new();
+        set.insert(start, end);
+        set
+    }
+
+    pub fn intervals(&self) -> &[(i32, i32)] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    // Number of integers in the set.
+    pub fn total_length(&self) -> i64 {
+        self.intervals
+            .iter()
+            .map(|&(start, end)| (end - start) as i64)
+            .sum()
+    }
+
+    pub fn contains(&self, value: i32) -> bool {
+        // First interval that ends after `value`
+        let i = self.intervals.partition_point(|&(_, end)| end <= value);
+        i < self.intervals.len() && self.intervals[i].0 <= value
+    }
+
+    // Add [start, end), merging it with the intervals it overlaps or touches.
+    pub fn insert(&mut self, start: i32, end: i32) {
+        if start >= end {
+            return;
+        }
+        let first = self.intervals.partition_point(|&(_, e)| e < start);
+        let last = self.intervals.partition_point(|&(s, _)| s <= end);
+        let merged = if first < last {
+            (
+                min(start, self.intervals[first].0),
+                max(end, self.intervals[last - 1].1),
+            )
+        } else {
+            (start, end)
+        };
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut set = self.clone();
+        for &(start, end) in &other.intervals {
+            set.insert(start, end);
+        }
+        set
+    }
+
+    pub fn subtract(&self, other: &IntervalSet) -> IntervalSet {
+        let mut set = IntervalSet::new();
+        let mut others = other.intervals.iter().peekable();
+        for &(start, end) in &self.intervals {
+            let mut from = start;
+            while let Some(&&(other_start, other_end)) = others.peek() {
+                if other_end <= from {
+                    others.next();
+                    continue;
+                }
+                if other_start >= end {
+                    break;
+                }
+                if other_start > from {
+                    set.intervals.push((from, other_start));
+                }
+                from = other_end;
+                // It may also cut into the next interval
+                if other_end >= end {
+                    break;
+                }
+                others.next();
+            }
+            if from < end {
+                set.intervals.push((from, end));
+            }
+        }
+        set
+    }
+
+    // What's missing from the set within [start, end).
+    pub fn gaps(&self, start: i32, end: i32) -> IntervalSet {
+        IntervalSet::from_interval(start, end).subtract(self)
+    }
+}
+
+impl FromIterator<(i32, i32)> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = (i32, i32)>>(iter: T) -> Self {
+        let mut set = IntervalSet::new();
+        for (start, end) in iter {
+            set.insert(start, end);
+        }
+        set
+    }
+}
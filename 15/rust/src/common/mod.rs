@@ -0,0 +1,142 @@
+pub mod coverage;
+pub mod interval;
+pub mod search;
+
+use geo::coord;
+use geo::geometry::{Coord, Rect};
+
+use interval::IntervalSet;
+
+pub struct Sensor {
+    pub location: Coord<i32>,
+    pub closest_beacon: Coord<i32>,
+}
+
+impl Sensor {
+    pub fn coverage_radius(&self) -> i32 {
+        let (a, b) = (self.location - self.closest_beacon).x_y();
+        a.abs() + b.abs()
+    }
+
+    pub fn covers(&self, point: Coord<i32>) -> bool {
+        let (a, b) = (self.location - point).x_y();
+        a.abs() + b.abs() <= self.coverage_radius()
+    }
+
+    pub fn bounding_box(&self) -> Rect<i32> {
+        Rect::new(self.location, self.location + coord! { x: 1, y: 1})
+    }
+
+    pub fn coverage_bounding_box(&self) -> Rect<i32> {
+        let bounds = self.bounding_box();
+        let coverage_dist = self.coverage_radius();
+        let delta = coord! { x: coverage_dist, y: coverage_dist };
+        Rect::new(bounds.min() - delta, bounds.max() + delta)
+    }
+
+    // Positions covered on row `y` as [min, max), if any.
+    pub fn coverage_on_row(&self, y: i32) -> Option<(i32, i32)> {
+        // In manathan distance intersection is simplified, when we are in bounding box:
+        // ------#-------
+        // =====###======
+        // ----##O##-----
+        // -----###------
+        // ------#-------
+        // min = sensor_min_bound.x - (coverage_radius - abs(y - sensor_min_bound.y))
+        // max = sensor_max_bound.x + (coverage_radius - abs(y - sensor_min_bound.y))
+        let coverage_bounds = self.coverage_bounding_box();
+        if y < coverage_bounds.min().y || coverage_bounds.max().y <= y {
+            return None;
+        }
+        let bounds = self.bounding_box();
+        let width = self.coverage_radius() - (y - bounds.min().y).abs();
+        Some((bounds.min().x - width, bounds.max().x + width))
+    }
+}
+
+// All positions covered by `sensors` on row `y`.
+pub fn coverage_on_row(sensors: &[Sensor], y: i32) -> IntervalSet {
+    sensors
+        .iter()
+        .filter_map(|sensor| sensor.coverage_on_row(y))
+        .collect()
+}
+
+// Sensor lists come either as the puzzle's reports:
+//
+//   Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+//
+// or in a simpler, hand-made format:
+//
+//   2,18:-2,15
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    Report,
+    Simple,
+}
+
+impl Format {
+    // Guess the format from the first line that isn't blank.
+    pub fn detect(input: &str) -> Format {
+        match input.lines().find(|line| !line.trim().is_empty()) {
+            Some(line) if !line.trim_start().starts_with("Sensor") => Format::Simple,
+            _ => Format::Report,
+        }
+    }
+}
+
+fn parse_coord(text: &str, format: Format) -> Result<Coord<i32>, String> {
+    let (x, y) = text
+        .split_once(',')
+        .ok_or_else(|| format!("expected a position, found '{}'", text.trim()))?;
+    let (x, y) = match format {
+        Format::Simple => (x.trim(), y.trim()),
+        Format::Report => (
+            x.trim()
+                .strip_prefix("x=")
+                .ok_or_else(|| format!("expected 'x=' in '{}'", text.trim()))?,
+            y.trim()
+                .strip_prefix("y=")
+                .ok_or_else(|| format!("expected 'y=' in '{}'", text.trim()))?,
+        ),
+    };
+    let number = |value: &str| {
+        value
+            .parse::<i32>()
+            .map_err(|_| format!("'{value}' is not a valid coordinate"))
+    };
+    Ok(coord! { x: number(x)?, y: number(y)? })
+}
+
+fn parse_sensor(line: &str, format: Format) -> Result<Sensor, String> {
+    let line = line.trim();
+    let (location, beacon) = match format {
+        Format::Simple => line.split_once(':'),
+        Format::Report => line
+            .strip_prefix("Sensor at ")
+            .and_then(|rest| rest.split_once(": closest beacon is at ")),
+    }
+    .ok_or_else(|| format!("expected a sensor, found '{line}'"))?;
+    Ok(Sensor {
+        location: parse_coord(location, format)?,
+        closest_beacon: parse_coord(beacon, format)?,
+    })
+}
+
+// Blank lines are skipped, other lines must hold a sensor in `format`.
+pub fn parse_sensors(input: &str, format: Format) -> Result<Vec<Sensor>, String> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(num, line)| {
+            parse_sensor(line, format).map_err(|err| format!("line {}: {err}", num + 1))
+        })
+        .collect()
+}
+
+// Reads all of stdin, in whichever format it comes.
+pub fn read_sensors() -> Vec<Sensor> {
+    let input = std::io::read_to_string(std::io::stdin()).unwrap();
+    parse_sensors(&input, Format::detect(&input)).unwrap_or_else(|err| panic!("{err}"))
+}
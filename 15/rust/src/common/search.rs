@@ -0,0 +1,166 @@
+use geo::coord;
+use geo::geometry::{Coord, Rect};
+use rayon::prelude::*;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicI32, Ordering};
+
+use super::coverage::RotatedRect;
+use super::{coverage_on_row, Sensor};
+
+// Row scans report progress, as the number of rows scanned so far, every
+// `SCAN_CHUNK` rows.
+pub const SCAN_CHUNK: i32 = 10000;
+
+// Positions on row `y` of the search square [0, size] x [0, size] covered by
+// no sensor.
+fn row_gaps(sensors: &[Sensor], y: i32, size: i32) -> Vec<Coord<i32>> {
+    coverage_on_row(sensors, y)
+        .gaps(0, size + 1)
+        .intervals()
+        .iter()
+        .flat_map(|&(start, end)| (start..end).map(move |x| coord! { x: x, y: y }))
+        .collect()
+}
+
+// Brute-force: scan the search square row by row, and stop at the first row
+// whose coverage has gaps. Returns the positions in these gaps.
+//
+// This is the reference the other searches are checked against.
+pub fn scan_rows<F>(sensors: &[Sensor], size: i32, mut progress: F) -> Vec<Coord<i32>>
+where
+    F: FnMut(i32),
+{
+    for y_scan in 0..=size {
+        if y_scan > 0 && y_scan % SCAN_CHUNK == 0 {
+            progress(y_scan);
+        }
+        let gaps = row_gaps(sensors, y_scan, size);
+        if !gaps.is_empty() {
+            return gaps;
+        }
+    }
+    progress(size + 1);
+    Vec::new()
+}
+
+// Same as `scan_rows`, with chunks of rows scanned in parallel. Once a gap is
+// found, chunks further down give up: they can't hold the first row with gaps.
+// `progress` may be called from any thread, and calls may arrive slightly out
+// of order.
+pub fn par_scan_rows<F>(sensors: &[Sensor], size: i32, progress: F) -> Vec<Coord<i32>>
+where
+    F: Fn(i32) + Sync,
+{
+    let found = AtomicI32::new(i32::MAX);
+    let scanned = AtomicI32::new(0);
+    let chunks = size / SCAN_CHUNK + 1;
+    (0..chunks)
+        .into_par_iter()
+        .filter_map(|chunk| {
+            let start = chunk * SCAN_CHUNK;
+            let end = std::cmp::min(start + SCAN_CHUNK, size + 1);
+            let mut gaps = Vec::new();
+            let mut y_scan = start;
+            while y_scan < end && y_scan <= found.load(Ordering::Relaxed) {
+                gaps = row_gaps(sensors, y_scan, size);
+                y_scan += 1;
+                if !gaps.is_empty() {
+                    found.fetch_min(y_scan - 1, Ordering::Relaxed);
+                    break;
+                }
+            }
+            let rows = y_scan - start;
+            if rows > 0 {
+                progress(scanned.fetch_add(rows, Ordering::Relaxed) + rows);
+            }
+            (!gaps.is_empty()).then_some(gaps)
+        })
+        .min_by_key(|gaps| gaps[0].y)
+        .unwrap_or_default()
+}
+
+// A `progress` for the row scans that keeps a status line up to date on
+// stderr, e.g. "Scanned 120000/4000001 rows (3%)". The line is left open for
+// the caller to end once the scan is over.
+pub fn print_progress(size: i32) -> impl Fn(i32) + Sync {
+    move |scanned| {
+        let total = size as i64 + 1;
+        let mut stderr = io::stderr().lock();
+        write!(
+            stderr,
+            "\rScanned {scanned}/{total} rows ({}%)",
+            scanned as i64 * 100 / total
+        )
+        .unwrap();
+    }
+}
+
+// Uncovered positions sit right outside the coverage of some sensors: on the
+// diamond at distance `radius + 1` around them. In `u = x + y`, `v = x - y`
+// coordinates, the edges of these diamonds are the lines `u = a` and `v = b`.
+// Together with the sides of the search square, these lines cut the (u, v)
+// plane into boxes, a few thousands for the puzzle input, inside which
+// coverage doesn't change.
+//
+// So we check one corner of each box, and list the positions in the square of
+// the boxes covered by no sensor. This finds every uncovered position, be it
+// isolated (like the puzzle's distress beacon) or in a bigger region.
+pub fn search_edges(sensors: &[Sensor], size: i32) -> Vec<Coord<i32>> {
+    // Boxes are half-open, [u_lines[i], u_lines[i + 1]) x [v_lines[j],
+    // v_lines[j + 1]): the lines past a sensor's coverage open a box, the ones
+    // before it close one.
+    let mut u_lines = vec![0, 2 * size + 1]; // corners (0,0) and (size,size)
+    let mut v_lines = vec![-size, size + 1]; // corners (0,size) and (size,0)
+    for sensor in sensors {
+        let (x, y) = sensor.location.x_y();
+        let reach = sensor.coverage_radius() + 1;
+        u_lines.extend([x + y - reach + 1, x + y + reach]);
+        v_lines.extend([x - y - reach + 1, x - y + reach]);
+    }
+    for (lines, range) in [
+        (&mut u_lines, 0..=2 * size + 1),
+        (&mut v_lines, -size..=size + 1),
+    ] {
+        lines.retain(|line| range.contains(line));
+        lines.sort_unstable();
+        lines.dedup();
+    }
+
+    let rects = sensors
+        .iter()
+        .map(RotatedRect::of_sensor)
+        .collect::<Vec<_>>();
+    let square = Rect::new(coord! { x: 0, y: 0 }, coord! { x: size + 1, y: size + 1 });
+    let mut uncovered = Vec::new();
+    for u in u_lines.windows(2) {
+        for v in v_lines.windows(2) {
+            // Boxes in the corners of the (u, v) plane are outside the square
+            if u[0] + v[0] > 2 * size
+                || u[1] + v[1] - 2 < 0
+                || u[0] - (v[1] - 1) > 2 * size
+                || (u[1] - 1) - v[0] < 0
+            {
+                continue;
+            }
+            let covered = rects.iter().any(|rect| {
+                (rect.u.0..rect.u.1).contains(&u[0]) && (rect.v.0..rect.v.1).contains(&v[0])
+            });
+            if covered {
+                continue;
+            }
+            let cell = RotatedRect {
+                u: (u[0], u[1]),
+                v: (v[0], v[1]),
+            };
+            for (u, start, end) in cell.rows_within(square) {
+                // (u, v) is a position when u and v have the same parity
+                let first = start + (start - u).rem_euclid(2);
+                for v in (first..end).step_by(2) {
+                    uncovered.push(coord! { x: (u + v) / 2, y: (u - v) / 2 });
+                }
+            }
+        }
+    }
+    uncovered.sort_unstable_by(|a, b| a.y.cmp(&b.y).then(a.x.cmp(&b.x)));
+    uncovered
+}
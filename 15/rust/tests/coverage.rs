@@ -0,0 +1,69 @@
+use day15::common::coverage::CoverageMap;
+use day15::common::*;
+use geo::coord;
+use geo::geometry::Rect;
+use shared::random::Lcg;
+
+#[test]
+fn coverage_matches_brute_force() {
+    let mut lcg = Lcg::new(39);
+    let mut random = |range: i32| lcg.below(range as u64) as i32;
+    for _ in 0..300 {
+        let sensors = (0..1 + random(5))
+            .map(|_| {
+                let location = coord! { x: random(30) - 10, y: random(30) - 10 };
+                let beacon = location + coord! { x: random(13) - 6, y: random(13) - 6 };
+                Sensor {
+                    location,
+                    closest_beacon: beacon,
+                }
+            })
+            .collect::<Vec<_>>();
+        let map = CoverageMap::new(&sensors);
+        let covered = |x: i32, y: i32| {
+            let point = coord! { x: x, y: y };
+            sensors.iter().any(|sensor| sensor.covers(point))
+        };
+
+        // Sensors can't reach past [-22, 32)
+        let mut area = 0;
+        for y in -22..32 {
+            for x in -22..32 {
+                assert_eq!(map.is_covered(coord! { x: x, y: y }), covered(x, y));
+                area += covered(x, y) as i64;
+            }
+        }
+        assert_eq!(map.area(), area);
+
+        let (x, y) = (random(30) - 15, random(30) - 15);
+        let within = Rect::new(
+            coord! { x: x, y: y },
+            coord! { x: x + 1 + random(20), y: y + 1 + random(20) },
+        );
+        let positions = (within.min().y..within.max().y)
+            .flat_map(|y| (within.min().x..within.max().x).map(move |x| (x, y)))
+            .collect::<Vec<_>>();
+        let covered_within = positions.iter().filter(|&&(x, y)| covered(x, y)).count();
+        assert_eq!(map.area_within(within), covered_within as i64);
+
+        // Uncovered regions don't overlap, and hold all uncovered positions
+        let regions = map.uncovered(within);
+        for &(x, y) in &positions {
+            let (u, v) = (x + y, x - y);
+            let holding = regions
+                .iter()
+                .filter(|region| {
+                    (region.rect.u.0..region.rect.u.1).contains(&u)
+                        && (region.rect.v.0..region.rect.v.1).contains(&v)
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(holding.len(), !covered(x, y) as usize, "{x},{y}");
+            for region in holding {
+                assert!(region.bounds.min().x <= x && x < region.bounds.max().x);
+                assert!(region.bounds.min().y <= y && y < region.bounds.max().y);
+            }
+        }
+        let size = regions.iter().map(|region| region.size).sum::<i64>();
+        assert_eq!(size, (positions.len() - covered_within) as i64);
+    }
+}
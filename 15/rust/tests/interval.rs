@@ -0,0 +1,90 @@
+use day15::common::interval::IntervalSet;
+use shared::random::Lcg;
+
+// Sets of integers within [LOW, HIGH), as a plain bitmap to check against.
+const LOW: i32 = -20;
+const HIGH: i32 = 40;
+type Bitmap = Vec<bool>;
+
+fn bitmap(set: &IntervalSet) -> Bitmap {
+    (LOW..HIGH).map(|value| set.contains(value)).collect()
+}
+
+// Intervals must be sorted, not empty, and neither overlap nor touch.
+fn check_canonical(set: &IntervalSet) {
+    for &(start, end) in set.intervals() {
+        assert!(start < end, "{set:?}");
+    }
+    for pair in set.intervals().windows(2) {
+        assert!(pair[0].1 < pair[1].0, "{set:?}");
+    }
+}
+
+fn random_set(random: &mut impl FnMut(i32) -> i32) -> (IntervalSet, Bitmap) {
+    let mut set = IntervalSet::new();
+    let mut expected = vec![false; (HIGH - LOW) as usize];
+    for _ in 0..random(6) {
+        // Some empty or reversed intervals too
+        let start = LOW + random(HIGH - LOW);
+        let end = (start + random(15) - 2).min(HIGH);
+        set.insert(start, end);
+        for value in start..end {
+            expected[(value - LOW) as usize] = true;
+        }
+        check_canonical(&set);
+        assert_eq!(bitmap(&set), expected, "after inserting [{start}, {end})");
+    }
+    (set, expected)
+}
+
+#[test]
+fn sets_match_bitmaps() {
+    let mut lcg = Lcg::new(38);
+    let mut random = |range: i32| lcg.below(range as u64) as i32;
+    for _ in 0..2000 {
+        let (a, a_bits) = random_set(&mut random);
+        let (b, b_bits) = random_set(&mut random);
+        let count = |bits: &Bitmap| bits.iter().filter(|bit| **bit).count() as i64;
+        assert_eq!(a.total_length(), count(&a_bits), "{a:?}");
+        assert_eq!(a.is_empty(), count(&a_bits) == 0, "{a:?}");
+        // Nothing outside of what was inserted
+        assert!(!a.contains(LOW - 1) && !a.contains(HIGH + 20));
+
+        let union = a.union(&b);
+        check_canonical(&union);
+        let expected = a_bits.iter().zip(&b_bits).map(|(a, b)| *a || *b);
+        assert_eq!(
+            bitmap(&union),
+            expected.collect::<Bitmap>(),
+            "{a:?} | {b:?}"
+        );
+
+        let difference = a.subtract(&b);
+        check_canonical(&difference);
+        let expected = a_bits.iter().zip(&b_bits).map(|(a, b)| *a && !*b);
+        assert_eq!(
+            bitmap(&difference),
+            expected.collect::<Bitmap>(),
+            "{a:?} - {b:?}"
+        );
+
+        let (start, end) = (LOW + random(HIGH - LOW), LOW + random(HIGH - LOW));
+        let gaps = a.gaps(start, end);
+        check_canonical(&gaps);
+        let expected = (LOW..HIGH).map(|value| (start..end).contains(&value) && !a.contains(value));
+        assert_eq!(
+            bitmap(&gaps),
+            expected.collect::<Bitmap>(),
+            "{a:?} gaps in [{start}, {end})"
+        );
+    }
+}
+
+#[test]
+fn touching_intervals_merge() {
+    let set = [(0, 2), (5, 7), (2, 5), (9, 10)]
+        .into_iter()
+        .collect::<IntervalSet>();
+    assert_eq!(set.intervals(), [(0, 7), (9, 10)]);
+    assert_eq!(set.gaps(-1, 11).intervals(), [(-1, 0), (7, 9), (10, 11)]);
+}
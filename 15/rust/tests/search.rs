@@ -0,0 +1,54 @@
+use day15::common::search::search_edges;
+use day15::common::*;
+use geo::coord;
+use geo::geometry::Coord;
+use shared::random::Lcg;
+
+// Every position of the search square covered by no sensor, row by row.
+fn brute_force(sensors: &[Sensor], size: i32) -> Vec<Coord<i32>> {
+    (0..=size)
+        .flat_map(|y| (0..=size).map(move |x| coord! { x: x, y: y }))
+        .filter(|point| !sensors.iter().any(|sensor| sensor.covers(*point)))
+        .collect()
+}
+
+#[test]
+fn example_beacon() {
+    let input = include_str!("../../example.txt");
+    let sensors = parse_sensors(input, Format::detect(input)).unwrap();
+    assert_eq!(search_edges(&sensors, 20), vec![coord! { x: 14, y: 11 }]);
+}
+
+#[test]
+fn uncovered_regions() {
+    // Nothing covered at all, a sensor leaving out the corners of the square,
+    // and two small sensors with one big uncovered region around them
+    for (input, size, count) in [
+        ("", 5, 36),
+        ("3,3:3,0", 6, 24),
+        ("2,2:2,0\n12,2:12,0", 14, 199),
+    ] {
+        let sensors = parse_sensors(input, Format::Simple).unwrap();
+        let found = search_edges(&sensors, size);
+        assert_eq!(found.len(), count, "{input:?}");
+        assert_eq!(found, brute_force(&sensors, size), "{input:?}");
+    }
+
+    // Random sensors, scattered in and around the square
+    let mut lcg = Lcg::new(15);
+    let mut random = |range: i32| lcg.below(range as u64) as i32;
+    for _ in 0..200 {
+        let size = 1 + random(30);
+        let sensors = (0..random(6))
+            .map(|_| {
+                let location = coord! { x: random(size + 11) - 5, y: random(size + 11) - 5 };
+                let beacon = location + coord! { x: random(11) - 5, y: random(11) - 5 };
+                Sensor {
+                    location,
+                    closest_beacon: beacon,
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(search_edges(&sensors, size), brute_force(&sensors, size));
+    }
+}
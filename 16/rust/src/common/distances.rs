@@ -0,0 +1,59 @@
+use petgraph::visit::EdgeRef;
+
+use super::ValveNetwork;
+
+// Distance between valves that can't reach each other. It's far beyond any
+// time budget, yet adding two of them can't overflow.
+pub const UNREACHABLE: i32 = i32::MAX / 4;
+
+// Shortest time to go from any valve to any other, in a dense matrix indexed
+// like the nodes of the network it was computed from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Distances {
+    size: usize,
+    matrix: Vec<i32>,
+}
+
+impl Distances {
+    // Floyd–Warshall: for each valve `k` in turn, check if going through `k`
+    // is a shortcut between any two valves.
+    //
+    // - https://en.wikipedia.org/wiki/Floyd%E2%80%93Warshall_algorithm
+    pub fn from_network(graph: &ValveNetwork) -> Distances {
+        let size = graph.node_count();
+        let mut matrix = vec![UNREACHABLE; size * size];
+        for i in 0..size {
+            matrix[i * size + i] = 0;
+        }
+        for edge in graph.edge_references() {
+            let (a, b) = (edge.source().index(), edge.target().index());
+            let weight = std::cmp::min(matrix[a * size + b], *edge.weight());
+            matrix[a * size + b] = weight;
+            matrix[b * size + a] = weight;
+        }
+        for k in 0..size {
+            for i in 0..size {
+                let through = matrix[i * size + k];
+                if through == UNREACHABLE {
+                    continue;
+                }
+                for j in 0..size {
+                    let distance = through + matrix[k * size + j];
+                    if distance < matrix[i * size + j] {
+                        matrix[i * size + j] = distance;
+                    }
+                }
+            }
+        }
+        Distances { size, matrix }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    // UNREACHABLE if there's no way from `a` to `b`.
+    pub fn get(&self, a: usize, b: usize) -> i32 {
+        self.matrix[a * self.size + b]
+    }
+}
@@ -0,0 +1,71 @@
+use petgraph::dot::{Config, Dot};
+use petgraph::graph::EdgeReference;
+use petgraph::visit::EdgeRef;
+use std::collections::HashMap;
+
+use super::plan::Plan;
+use super::{Valve, ValveNetwork};
+
+// One color per player, they go around if there are more players.
+const COLORS: [&str; 4] = ["red", "blue", "darkgreen", "orange"];
+
+// Graphviz description of `graph`, e.g. to render with:
+//
+//   dot -Tsvg reduced.dot -o reduced.svg
+//
+// Valves are labelled with their flow rate, and tunnels with how long they
+// take when they take more than a minute. When given a `plan`, the valves it
+// opens are filled, and the way to them is drawn, in the color of the player.
+// `tunnels` tells whether `graph` is the full network, where players go
+// through every tunnel, or a reduced one where they go straight to the next
+// valve.
+pub fn to_dot(graph: &ValveNetwork, plan: Option<&Plan>, tunnels: bool) -> String {
+    let mut opened = HashMap::new();
+    let mut route = HashMap::new();
+    if let Some(plan) = plan {
+        for (player, legs) in plan.legs(graph, tunnels).iter().enumerate() {
+            let color = COLORS[player % COLORS.len()];
+            for leg in legs {
+                opened.insert(leg[leg.len() - 1], color);
+                for pair in leg.windows(2) {
+                    route.insert((pair[0].min(pair[1]), pair[0].max(pair[1])), color);
+                }
+            }
+        }
+    }
+    let start = plan.map(|plan| plan.start.as_str());
+
+    let node_attributes = |_, (node, valve): (_, &Valve)| {
+        let mut attributes = vec![format!("label = \"{}\\n{}\"", valve.name, valve.flow_rate)];
+        if let Some(color) = opened.get(&node) {
+            attributes.push(format!("style = filled fillcolor = {color}"));
+        } else if valve.flow_rate == 0 {
+            attributes.push("color = gray fontcolor = gray".to_string());
+        }
+        // On a reduced network, where players start has no flow
+        if Some(valve.name.as_str()) == start && (tunnels || valve.flow_rate == 0) {
+            attributes.push("shape = doublecircle".to_string());
+        }
+        attributes.join(" ")
+    };
+    let edge_attributes = |_, edge: EdgeReference<'_, i32>| {
+        let (a, b) = (edge.source(), edge.target());
+        let mut attributes = Vec::new();
+        if !tunnels || *edge.weight() != 1 {
+            attributes.push(format!("label = \"{}\"", edge.weight()));
+        }
+        if let Some(color) = route.get(&(a.min(b), a.max(b))) {
+            attributes.push(format!("color = {color} penwidth = 3"));
+        }
+        attributes.join(" ")
+    };
+    format!(
+        "{:?}",
+        Dot::with_attr_getters(
+            graph,
+            &[Config::NodeNoLabel, Config::EdgeNoLabel],
+            &edge_attributes,
+            &node_attributes,
+        )
+    )
+}
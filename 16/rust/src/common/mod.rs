@@ -0,0 +1,509 @@
+pub mod distances;
+pub mod dot;
+pub mod plan;
+pub mod subsets;
+pub mod valve_set;
+
+use petgraph::{
+    graph::{Graph, NodeIndex},
+    Undirected,
+};
+use regex::Regex;
+use shared::ttable::{self, Replacement};
+use std::cmp::max;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use distances::{Distances, UNREACHABLE};
+use valve_set::ValveSet;
+
+// This problem can be conceptualised as a backtracking algorithms. In game
+// theory, backtracking algorithms is the building block to minmax. The way to
+// understand how it works is to ask: "Given a particular state in the game,
+// what is my next best move?", or in this particular problem, given a current
+// position a set of open valves, what is the next move the maximizes the amount
+// of pressure that can be released?
+//
+// Backtracking algorithm compute the solution to this answer by starting from
+// the deepest possible move and working backward. By working backwards from the
+// tree of all possible moves, the algorithm eventually builds up to the best
+// move starting from the root position.
+//
+// In this solution, we also add a transposition table, which stores the best
+// pressure that we've seen so far for a given position (at), past valves
+// visited, time and number of players remaining. This saves _a lot_ of
+// computation cycles.
+//
+// The transposition table is also what we use to reconstruct the best set of
+// moves (also called Principal variation), see `plan.rs`.
+//
+// On top of that, branch and bound: before going down a move, we compute an
+// optimistic bound of what it can release (see `upper_bound`). Moves are
+// tried best bound first, and once the bound of a move can't beat the best
+// move found so far at this node, it and all the following ones are cut.
+// Cuts only compare moves of the same node, so what a node returns is still
+// exact and can go to the transposition table.
+//
+// This should compute solutions in a matter of seconds.
+//
+// Resources:
+// - https://en.wikipedia.org/wiki/Backtracking
+// - https://en.wikipedia.org/wiki/Transposition_table
+// - https://en.wikipedia.org/wiki/Branch_and_bound
+
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct Valve {
+    pub name: String,
+    pub flow_rate: i32,
+}
+
+impl Display for Valve {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} rate:{}", self.name, self.flow_rate)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Entry {
+    // Entry in the transposition table, is represented by:
+    // - time
+    // - current position
+    // - number of players (part 2)
+    // - past visited (and opened) valves compressed to a bit vector.
+    //
+    // Transposition tables entry take most of the memory of the program (the
+    // table has a fixed number of them), so to fit more of them, I used a bit
+    // vector to represent set of opened valves. I could compress `time_left`
+    // and `at` further into `i16` each, but not really worth the complexity.
+    //
+    // The graph is reduced to valves with flow_rates > 0 only (save for the start),
+    // so the single 64 bit word held inline is enough for the puzzle input.
+    // Bigger networks take more words on the heap, which the table counts
+    // against its memory budget.
+    time_left: i32,
+    at: NodeIndex,
+    players: usize,
+    valve_bitset: ValveSet,
+}
+
+impl Entry {
+    fn from_state(path: &Vec<NodeIndex>, time_left: i32, players: usize, valves: usize) -> Entry {
+        let mut valve_bitset = ValveSet::new(valves);
+        for valve in path {
+            valve_bitset.insert(valve.index());
+        }
+        Entry {
+            time_left,
+            at: path[path.len() - 1],
+            players,
+            valve_bitset,
+        }
+    }
+}
+
+// Transposition table maps an Entry to a max pressure (i32). We store only
+// exact solutions here. It has a fixed size (see `Config::memory`): entries
+// that don't fit get computed again.
+pub type TTable = ttable::TTable<Entry, i32>;
+
+pub type ValveNetwork = Graph<Valve, i32, Undirected>;
+
+// Reads the scan of the valves, one per line:
+//
+//   Valve BB has flow rate=13; tunnels lead to valves CC, AA
+//
+// This graphs contains all nodes (even those with flow rate = 0) with weight = 1
+pub fn parse_network(input: &str) -> ValveNetwork {
+    let re: Regex = Regex::new(
+        r"Valve (\w{2}) has flow rate=(\d+); tunnels? leads? to valves? (\w{2}(?:, (\w{2}))*)",
+    )
+    .unwrap();
+
+    let captures: Vec<(String, i32, String)> = input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| -> (String, i32, String) {
+            let caps = re
+                .captures_iter(line)
+                .next()
+                .expect("Unexpected format on standard input");
+            (
+                caps.get(1).unwrap().as_str().to_string(),
+                caps.get(2).unwrap().as_str().parse::<i32>().unwrap(),
+                caps.get(3).unwrap().as_str().to_string(),
+            )
+        })
+        .collect();
+
+    let mut graph = ValveNetwork::default();
+    // Add vertices (nodes) in a dictionary for construction only
+    let node_map = captures
+        .iter()
+        .map(|capture| {
+            (
+                capture.0.as_str(),
+                graph.add_node(Valve {
+                    name: capture.0.clone(),
+                    flow_rate: capture.1,
+                }),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+    // Add edges
+    for capture in &captures {
+        for neighbor in capture.2.split(", ") {
+            let (a, b) = (node_map[capture.0.as_str()], node_map[neighbor]);
+            if !graph.contains_edge(a, b) {
+                graph.add_edge(a, b, 1);
+            }
+        }
+    }
+    graph
+}
+
+// Finds a valve by name.
+pub fn find_valve(graph: &ValveNetwork, name: &str) -> Result<NodeIndex, String> {
+    graph
+        .node_indices()
+        .find(|node| graph[*node].name == name)
+        .ok_or_else(|| format!("valve {name} isn't in the network"))
+}
+
+// Only keeps valves with a flow rate (and `start`), with an edge between any
+// two of them weighted by how long it takes to go from one to the other.
+// Returns the reduced network and where players start in it.
+//
+// Valves keep the order they had in `graph`: the index of a valve is its rank
+// among the valves kept, from 0 to `node_count()` (excluded). This is what
+// `ValveSet` and `Distances` rely on.
+//
+// Players start at `start` without opening it, but it may still have a flow
+// rate worth opening it for. The solvers never go back to where a player
+// starts, so such a valve is kept twice: once with no flow as the starting
+// point, then as a valve to open, at a distance of 0.
+pub fn reduce_network(
+    mut graph: ValveNetwork,
+    start: &str,
+) -> Result<(ValveNetwork, NodeIndex), String> {
+    find_valve(&graph, start)?;
+    let order = graph
+        .node_indices()
+        .map(|node| (graph[node].name.clone(), node.index()))
+        .collect::<HashMap<_, _>>();
+
+    // Bypass nodes with "rate=0" unless it's the start by connecting their
+    // neighbor then remove them (saves cycles for next step). The bypass
+    // replaces any longer tunnel between the neighbors.
+    while let Some(node) = graph
+        .node_indices()
+        .find(|node| graph[*node].name != start && graph[*node].flow_rate == 0)
+    {
+        let mut neighbors_queue = graph.neighbors(node).collect::<Vec<_>>();
+        while let Some(neighbor) = neighbors_queue.pop() {
+            for other in neighbors_queue.iter() {
+                let weight = graph[graph.find_edge(neighbor, node).unwrap()]
+                    + graph[graph.find_edge(*other, node).unwrap()];
+                match graph.find_edge(neighbor, *other) {
+                    Some(edge) if graph[edge] <= weight => (),
+                    Some(edge) => graph[edge] = weight,
+                    None => {
+                        graph.add_edge(neighbor, *other, weight);
+                    }
+                }
+            }
+        }
+        graph.remove_node(node);
+    }
+
+    // Removing nodes moves the last node of the graph in place of the removed
+    // one, so the order is restored on a new graph. Finally, fully-connect the
+    // graph (save cycles later) with shortest distances, so we know how long
+    // it takes to go from any valve to any other.
+    let mut nodes = graph.node_indices().collect::<Vec<_>>();
+    nodes.sort_by_key(|node| order[&graph[*node].name]);
+    let mut kept = Vec::new();
+    for node in nodes {
+        let valve = graph[node].clone();
+        if valve.name == start && valve.flow_rate > 0 {
+            let name = valve.name.clone();
+            kept.push((node, Valve { name, flow_rate: 0 }));
+        }
+        kept.push((node, valve));
+    }
+
+    let distances = Distances::from_network(&graph);
+    let mut reduced = ValveNetwork::with_capacity(kept.len(), kept.len() * kept.len() / 2);
+    let index = kept
+        .iter()
+        .map(|(_, valve)| reduced.add_node(valve.clone()))
+        .collect::<Vec<_>>();
+    for (i, (a, _)) in kept.iter().enumerate() {
+        for (j, (b, _)) in kept.iter().enumerate().skip(i + 1) {
+            let distance = distances.get(a.index(), b.index());
+            if distance != UNREACHABLE {
+                reduced.add_edge(index[i], index[j], distance);
+            }
+        }
+    }
+
+    // See `dot.rs` if you want to see what it looks like.
+    let start = find_valve(&reduced, start)?;
+    Ok((reduced, start))
+}
+
+// What a run of the solver looks like.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    // Valve where everyone starts
+    pub start: String,
+    // Before the volcano erupts
+    pub minutes: i32,
+    // You, plus the elephants
+    pub players: usize,
+    // Spent teaching the elephants, if there are any
+    pub teaching: i32,
+    // Cut moves that can't beat the best one, see `Solver::upper_bound`
+    pub pruning: bool,
+    // Bytes for the transposition table, and which entries it keeps
+    pub memory: usize,
+    pub replacement: Replacement,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            start: "AA".to_string(),
+            minutes: 30,
+            players: 1,
+            teaching: 4,
+            pruning: true,
+            memory: ttable::DEFAULT_MEMORY,
+            replacement: Replacement::DepthPreferred,
+        }
+    }
+}
+
+impl Config {
+    // Time each player has to open valves.
+    pub fn time_budget(&self) -> i32 {
+        match self.players {
+            0 | 1 => self.minutes,
+            _ => self.minutes - self.teaching,
+        }
+    }
+}
+
+// Runs the backtracker on `network` (as parsed, it's reduced here).
+pub fn solve(
+    network: &ValveNetwork,
+    config: &Config,
+) -> Result<(plan::Plan, SearchStats, ttable::Stats), String> {
+    if config.players == 0 {
+        return Err("there must be at least one player".to_string());
+    }
+    if config.time_budget() <= 0 {
+        return Err(format!(
+            "no time left to open valves: {} minute(s), {} spent teaching",
+            config.minutes,
+            config.minutes - config.time_budget()
+        ));
+    }
+    let (graph, start) = reduce_network(network.clone(), &config.start)?;
+    let mut ttable = TTable::with_memory_and_heap(
+        config.memory,
+        ValveSet::heap_size(graph.node_count()),
+        config.replacement,
+    );
+    let mut solver = Solver::new(&graph, &mut ttable, config.time_budget(), start);
+    solver.pruning = config.pruning;
+    let plan = solver.best_plan(config.players);
+    let stats = solver.stats;
+    Ok((plan, stats, ttable.stats()))
+}
+
+// How much work the backtracker did.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    // Nodes searched, not found in the transposition table
+    pub explored: u64,
+    // Moves cut by the bound
+    pub pruned: u64,
+}
+
+impl Display for SearchStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} nodes explored, {} moves pruned",
+            self.explored, self.pruned
+        )
+    }
+}
+
+pub struct Solver<'a> {
+    pub graph: &'a ValveNetwork,
+    pub ttable: &'a mut TTable,
+    pub max_time: i32,
+    pub start: NodeIndex,
+    pub pruning: bool,
+    pub stats: SearchStats,
+    distances: Distances,
+    // Valves with a flow rate, highest first
+    by_flow: Vec<NodeIndex>,
+    // Shortest time to go to another valve and open it
+    min_step: i32,
+}
+
+impl<'a> Solver<'a> {
+    // Distances are read from `graph` once and for all.
+    pub fn new(
+        graph: &'a ValveNetwork,
+        ttable: &'a mut TTable,
+        max_time: i32,
+        start: NodeIndex,
+    ) -> Solver<'a> {
+        let distances = Distances::from_network(graph);
+        let mut by_flow = graph
+            .node_indices()
+            .filter(|node| graph[*node].flow_rate > 0)
+            .collect::<Vec<_>>();
+        by_flow.sort_by_key(|node| -graph[*node].flow_rate);
+        let size = distances.size();
+        let min_distance = (0..size)
+            .flat_map(|a| (0..size).filter(move |b| *b != a).map(move |b| (a, b)))
+            .map(|(a, b)| distances.get(a, b))
+            .min()
+            .unwrap_or(0);
+        Solver {
+            graph,
+            ttable,
+            max_time,
+            start,
+            pruning: true,
+            stats: SearchStats::default(),
+            distances,
+            by_flow,
+            min_step: min_distance + 1,
+        }
+    }
+
+    pub fn max_pressure(&mut self) -> i32 {
+        self.max_pressure_multiplayer(1)
+    }
+
+    pub fn max_pressure_multiplayer(&mut self, players: usize) -> i32 {
+        let mut path = Vec::<NodeIndex>::new();
+        // _max_pressure_impl will initialize variables from &self if we give a
+        // remaining time value of 0.
+        self._max_pressure_impl(&mut path, 0, players)
+    }
+
+    // Time left once the valve at `next` is opened, coming from `at`.
+    fn time_left_after(&self, at: NodeIndex, next: NodeIndex, time_left: i32) -> i32 {
+        // Open valve here before moving down
+        time_left - 1 - self.distances.get(at.index(), next.index())
+    }
+
+    // Most a move to `next` could release, opening it with `time_left`, given
+    // the valves already `visited` and the `players` left after this one.
+    //
+    // We pretend valves are all as close as the closest two, and that players
+    // open them highest flow rate first: each valve still closed is opened
+    // by whoever has the most time left, and that costs them `min_step`.
+    // Other players have all their time, as if they started next to a valve.
+    fn upper_bound(
+        &self,
+        visited: &ValveSet,
+        next: NodeIndex,
+        time_left: i32,
+        players: usize,
+    ) -> i32 {
+        let mut bound = time_left * self.graph[next].flow_rate;
+        let mut times_left = vec![time_left];
+        times_left.resize(players + 1, self.max_time);
+        for valve in self.by_flow.iter() {
+            if *valve == next || visited.contains(valve.index()) {
+                continue;
+            }
+            let time_left = times_left.iter_mut().max().unwrap();
+            *time_left -= self.min_step;
+            if *time_left <= 0 {
+                break;
+            }
+            bound += *time_left * self.graph[*valve].flow_rate;
+        }
+        bound
+    }
+
+    fn _max_pressure_impl(
+        &mut self,
+        path: &mut Vec<NodeIndex>,
+        time_left: i32,
+        players: usize,
+    ) -> i32 {
+        if time_left <= 0 {
+            if players == 0 {
+                return 0;
+            }
+            // Make next player play from start, but skip already visited nodes
+            path.push(self.start);
+            let pressure = self._max_pressure_impl(path, self.max_time, players - 1);
+            path.pop();
+            return pressure;
+        }
+
+        // Check transposition tables for known exact entry
+        let entry = Entry::from_state(path, time_left, players, self.graph.node_count());
+        if let Some(pressure) = self.ttable.get(&entry) {
+            return pressure;
+        }
+
+        self.stats.explored += 1;
+        let at = path[path.len() - 1];
+        // A player can also stop here and leave the other valves to the next
+        // player.
+        let mut pressure = match players {
+            0 => 0,
+            _ => self._max_pressure_impl(path, 0, players),
+        };
+
+        // Moves with their bound, most promising first
+        let mut moves = Vec::new();
+        for next in self.graph.node_indices() {
+            if entry.valve_bitset.contains(next.index()) {
+                continue;
+            }
+            let time_left = self.time_left_after(at, next, time_left);
+            let bound = match self.pruning {
+                true => self.upper_bound(&entry.valve_bitset, next, time_left, players),
+                false => i32::MAX,
+            };
+            moves.push((bound, next, time_left));
+        }
+        if self.pruning {
+            moves.sort_by_key(|(bound, _, _)| -bound);
+        }
+
+        for (i, &(bound, next, time_left)) in moves.iter().enumerate() {
+            if bound <= pressure {
+                self.stats.pruned += (moves.len() - i) as u64;
+                break;
+            }
+            // Too late to open `next`: no better than stopping here
+            if self.pruning && time_left <= 0 {
+                self.stats.pruned += 1;
+                continue;
+            }
+            path.push(next);
+            pressure = max(pressure, self._max_pressure_impl(path, time_left, players));
+            path.pop(); // restore state
+        }
+
+        // Store transposition, the more time the players have left the more
+        // it took to compute
+        pressure += time_left * self.graph[at].flow_rate;
+        let depth = time_left + players as i32 * self.max_time;
+        self.ttable.insert(entry, pressure, depth as u32);
+        pressure
+    }
+}
@@ -0,0 +1,252 @@
+use petgraph::algo::astar;
+use petgraph::graph::NodeIndex;
+use std::fmt::Display;
+
+use super::{Solver, ValveNetwork};
+
+// The best set of moves (principal variation) found by the solver. Minutes
+// count from 1 like in the puzzle: a player arrives at a valve at the end of
+// minute `arrival`, opens it during minute `opening`, and it releases pressure
+// from the minute after.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub valve: String,
+    pub flow_rate: i32,
+    pub arrival: i32,
+    pub opening: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Plan {
+    pub pressure: i32,
+    pub minutes: i32,
+    pub start: String,
+    // Valves opened by each player, in order
+    pub players: Vec<Vec<Step>>,
+}
+
+impl Step {
+    // Pressure released by this valve until time runs out.
+    pub fn released(&self, minutes: i32) -> i32 {
+        (minutes - self.opening) * self.flow_rate
+    }
+}
+
+// "You" for the first player, then the elephant(s).
+fn subject(player: usize) -> String {
+    match player {
+        0 => "You".to_string(),
+        1 => "The elephant".to_string(),
+        _ => format!("Elephant {player}"),
+    }
+}
+
+impl Solver<'_> {
+    // Same as `max_pressure_multiplayer`, but also tells how to get there. Once
+    // the transposition table is filled, the solver can be asked again about
+    // any state along the way for free: we follow the moves whose outcome adds
+    // up to the best pressure.
+    pub fn best_plan(&mut self, players: usize) -> Plan {
+        let pressure = self.max_pressure_multiplayer(players);
+        let mut plan = Plan {
+            pressure,
+            minutes: self.max_time,
+            start: self.graph[self.start].name.clone(),
+            players: Vec::new(),
+        };
+
+        let mut path = Vec::<NodeIndex>::new();
+        let (mut time_left, mut players_left) = (0, players);
+        loop {
+            if time_left <= 0 {
+                if players_left == 0 {
+                    break;
+                }
+                // Next player's turn, as in `_max_pressure_impl`
+                path.push(self.start);
+                time_left = self.max_time;
+                players_left -= 1;
+                plan.players.push(Vec::new());
+                continue;
+            }
+
+            let at = path[path.len() - 1];
+            let rest = self._max_pressure_impl(&mut path, time_left, players_left)
+                - time_left * self.graph[at].flow_rate;
+            if rest == 0 {
+                break;
+            }
+            let mut best = None;
+            for next in self.graph.node_indices() {
+                if path.contains(&next) {
+                    continue;
+                }
+                path.push(next);
+                let next_time_left = self.time_left_after(at, next, time_left);
+                let pressure = self._max_pressure_impl(&mut path, next_time_left, players_left);
+                path.pop();
+                if pressure == rest {
+                    best = Some((next, next_time_left));
+                    break;
+                }
+            }
+            match best {
+                Some((next, next_time_left)) => {
+                    path.push(next);
+                    time_left = next_time_left;
+                    if time_left > 0 {
+                        plan.players.last_mut().unwrap().push(Step {
+                            valve: self.graph[next].name.clone(),
+                            flow_rate: self.graph[next].flow_rate,
+                            arrival: self.max_time - time_left - 1,
+                            opening: self.max_time - time_left,
+                        });
+                    }
+                }
+                // The player stops here and leaves the rest to the next one
+                None => time_left = 0,
+            }
+        }
+        plan.players.resize(players, Vec::new());
+        plan
+    }
+}
+
+impl Plan {
+    // How each player gets to each valve they open on `graph`: the valves
+    // they go through, from the previous valve (or the start) to the one
+    // they open, both included. With `tunnels`, legs follow the shortest
+    // tunnels; otherwise, they are a single edge (for reduced networks).
+    pub fn legs(&self, graph: &ValveNetwork, tunnels: bool) -> Vec<Vec<Vec<NodeIndex>>> {
+        // Reduced networks may hold the start twice: the valve to open is
+        // the one with a flow rate.
+        let find = |name: &str, flow_rate: i32| {
+            let mut nodes = graph
+                .node_indices()
+                .filter(|node| graph[*node].name == name);
+            let first = nodes.next();
+            nodes
+                .find(|node| graph[*node].flow_rate == flow_rate)
+                .or(first)
+                .unwrap_or_else(|| panic!("Valve {name} isn't in the network"))
+        };
+        self.players
+            .iter()
+            .map(|steps| {
+                let mut from = find(&self.start, 0);
+                steps
+                    .iter()
+                    .map(|step| {
+                        let to = find(&step.valve, step.flow_rate);
+                        let leg = match tunnels {
+                            true => {
+                                astar(graph, from, |node| node == to, |_| 1, |_| 0)
+                                    .unwrap()
+                                    .1
+                            }
+                            false => vec![from, to],
+                        };
+                        from = to;
+                        leg
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Minute by minute account of the plan, as told in the puzzle:
+    //
+    //   == Minute 1 ==
+    //   No valves are open.
+    //   You move to valve DD.
+    //
+    // `network` is the full network, which tells which tunnels players go
+    // through between the valves they open.
+    pub fn replay(&self, network: &ValveNetwork) -> String {
+        // Valves each player goes through, minute by minute, until it stops
+        let moves = self
+            .legs(network, true)
+            .iter()
+            .zip(&self.players)
+            .map(|(legs, steps)| {
+                let mut moves = Vec::new();
+                for (leg, step) in legs.iter().zip(steps) {
+                    moves.extend(
+                        leg[1..]
+                            .iter()
+                            .map(|node| Some(network[*node].name.clone())),
+                    );
+                    // Tunnels may be shorter than what the plan allows for,
+                    // the player then waits at the valve.
+                    moves.resize(step.arrival as usize, None);
+                    moves.push(None); // opening the valve
+                }
+                moves
+            })
+            .collect::<Vec<_>>();
+
+        let mut replay = String::new();
+        for minute in 1..=self.minutes {
+            if minute > 1 {
+                replay.push('\n');
+            }
+            replay.push_str(&format!("== Minute {minute} ==\n"));
+
+            let mut open = self
+                .players
+                .iter()
+                .flatten()
+                .filter(|step| step.opening < minute)
+                .collect::<Vec<_>>();
+            open.sort_by(|a, b| a.valve.cmp(&b.valve));
+            let released = open.iter().map(|step| step.flow_rate).sum::<i32>();
+            let names = open
+                .iter()
+                .map(|step| step.valve.as_str())
+                .collect::<Vec<_>>();
+            match names[..] {
+                [] => replay.push_str("No valves are open.\n"),
+                [one] => replay.push_str(&format!(
+                    "Valve {one} is open, releasing {released} pressure.\n"
+                )),
+                [first, second] => replay.push_str(&format!(
+                    "Valves {first} and {second} are open, releasing {released} pressure.\n"
+                )),
+                [.., last] => replay.push_str(&format!(
+                    "Valves {}, and {last} are open, releasing {released} pressure.\n",
+                    names[..names.len() - 1].join(", ")
+                )),
+            }
+
+            for (player, steps) in self.players.iter().enumerate() {
+                let who = subject(player);
+                let s = if player == 0 { "" } else { "s" };
+                if let Some(step) = steps.iter().find(|step| step.opening == minute) {
+                    replay.push_str(&format!("{who} open{s} valve {}.\n", step.valve));
+                } else if let Some(Some(valve)) = moves[player].get(minute as usize - 1) {
+                    replay.push_str(&format!("{who} move{s} to valve {valve}.\n"));
+                }
+            }
+        }
+        replay
+    }
+}
+
+impl Display for Plan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (player, steps) in self.players.iter().enumerate() {
+            writeln!(f, "{}:", subject(player))?;
+            for step in steps {
+                writeln!(
+                    f,
+                    "  {}: arrive at minute {}, open at minute {}, releases {}",
+                    step.valve,
+                    step.arrival,
+                    step.opening,
+                    step.released(self.minutes)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use petgraph::graph::NodeIndex;
+use std::cmp::max;
+
+use super::distances::Distances;
+use super::ValveNetwork;
+
+// Another way to solve the puzzle, which shines with several players: players
+// open valves independently of each other, as long as no valve is opened
+// twice. So we compute, in a single pass, the best pressure one player can
+// release for every subset of valves, then pick disjoint subsets for the
+// players.
+//
+// Subsets are bit masks over the valves of the reduced network (start
+// excluded). There are 2^n of them, so this only works for a few dozen
+// valves at most, which is fine for the puzzle input (15 valves).
+//
+// Resources:
+// - https://en.wikipedia.org/wiki/Dynamic_programming
+// - https://cp-algorithms.com/algebra/all-submasks.html
+
+// Past this, the table of subsets doesn't fit in memory anymore.
+pub const MAX_VALVES: usize = 24;
+
+pub struct SubsetSolver {
+    // Valves that can be opened, bit `i` of a subset stands for `valves[i]`
+    valves: Vec<NodeIndex>,
+    // Best pressure one player can release opening only valves of the subset
+    best: Vec<i32>,
+}
+
+impl SubsetSolver {
+    pub fn new(
+        graph: &ValveNetwork,
+        start: NodeIndex,
+        max_time: i32,
+    ) -> Result<SubsetSolver, String> {
+        let valves = graph
+            .node_indices()
+            .filter(|node| *node != start)
+            .collect::<Vec<_>>();
+        if valves.len() > MAX_VALVES {
+            return Err(format!(
+                "{} valves to open, the subset solver can't do more than {MAX_VALVES}",
+                valves.len()
+            ));
+        }
+        let mut solver = SubsetSolver {
+            best: vec![0; 1 << valves.len()],
+            valves,
+        };
+        let distances = Distances::from_network(graph);
+        solver.visit(graph, &distances, start, max_time, 0, 0);
+
+        // Opening fewer valves is always an option: make each subset as good
+        // as the best of its subsets, one valve at a time.
+        for i in 0..solver.valves.len() {
+            for subset in 0..solver.best.len() {
+                if subset & (1 << i) != 0 {
+                    solver.best[subset] = max(solver.best[subset], solver.best[subset ^ (1 << i)]);
+                }
+            }
+        }
+        Ok(solver)
+    }
+
+    // Every order a player can open valves in, recording what each subset of
+    // opened valves releases.
+    fn visit(
+        &mut self,
+        graph: &ValveNetwork,
+        distances: &Distances,
+        at: NodeIndex,
+        time_left: i32,
+        opened: usize,
+        pressure: i32,
+    ) {
+        self.best[opened] = max(self.best[opened], pressure);
+        for i in 0..self.valves.len() {
+            if opened & (1 << i) != 0 {
+                continue;
+            }
+            let next = self.valves[i];
+            // Open valve there once we get there
+            let time_left = time_left - 1 - distances.get(at.index(), next.index());
+            if time_left <= 0 {
+                continue;
+            }
+            let pressure = pressure + time_left * graph[next].flow_rate;
+            self.visit(
+                graph,
+                distances,
+                next,
+                time_left,
+                opened | (1 << i),
+                pressure,
+            );
+        }
+    }
+
+    pub fn max_pressure(&self) -> i32 {
+        self.max_pressure_multiplayer(1)
+    }
+
+    // Each extra player splits the valves left by the previous ones: the best
+    // for `k` players on a subset is the best split of that subset between one
+    // player and `k - 1` players. Two players only need one split of all the
+    // valves (2^n), more players go through all subsets of all subsets (3^n).
+    pub fn max_pressure_multiplayer(&self, players: usize) -> i32 {
+        let all = self.best.len() - 1;
+        if players == 0 {
+            return 0;
+        }
+        let mut others = self.best.clone();
+        for _ in 2..players {
+            others = (0..=all)
+                .map(|set| {
+                    let mut pressure = others[set];
+                    let mut subset = set;
+                    while subset != 0 {
+                        pressure = max(pressure, self.best[subset] + others[set ^ subset]);
+                        subset = (subset - 1) & set;
+                    }
+                    pressure
+                })
+                .collect();
+        }
+        match players {
+            1 => self.best[all],
+            _ => (0..=all)
+                .map(|subset| self.best[subset] + others[all ^ subset])
+                .max()
+                .unwrap(),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+// Set of valves, by their index in the reduced network, as a bit vector. The
+// first 64 valves, enough for the puzzle input, are held inline so sets don't
+// allocate. Bigger networks take one more word on the heap per 64 valves, so
+// there's no limit on the size of the network, but sets must be created for
+// the same number of valves to compare equal.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ValveSet {
+    first: u64,
+    rest: Vec<u64>,
+}
+
+impl ValveSet {
+    // Empty set for valves 0 to `valves` (excluded).
+    pub fn new(valves: usize) -> ValveSet {
+        ValveSet {
+            first: 0,
+            rest: vec![0; valves.div_ceil(64).saturating_sub(1)],
+        }
+    }
+
+    // Heap memory taken by each set for `valves` valves.
+    pub fn heap_size(valves: usize) -> usize {
+        valves.div_ceil(64).saturating_sub(1) * std::mem::size_of::<u64>()
+    }
+
+    fn word(&mut self, valve: usize) -> &mut u64 {
+        match valve / 64 {
+            0 => &mut self.first,
+            word => &mut self.rest[word - 1],
+        }
+    }
+
+    pub fn insert(&mut self, valve: usize) {
+        *self.word(valve) |= 1 << (valve % 64);
+    }
+
+    pub fn contains(&self, valve: usize) -> bool {
+        let word = match valve / 64 {
+            0 => self.first,
+            word => self.rest[word - 1],
+        };
+        word & (1 << (valve % 64)) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        let rest = self.rest.iter().map(|word| word.count_ones() as usize);
+        self.first.count_ones() as usize + rest.sum::<usize>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.first == 0 && self.rest.iter().all(|word| *word == 0)
+    }
+}
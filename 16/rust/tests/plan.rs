@@ -0,0 +1,128 @@
+use day16::common::plan::Plan;
+use day16::common::*;
+
+fn example_plan(players: usize) -> (Plan, ValveNetwork) {
+    let network = parse_network(include_str!("../../example.txt"));
+    let config = Config {
+        players,
+        ..Config::default()
+    };
+    let (plan, _, _) = solve(&network, &config).unwrap();
+    (plan, network)
+}
+
+// (valve, arrival, opening) for each valve a player opens.
+fn steps(plan: &Plan) -> Vec<Vec<(&str, i32, i32)>> {
+    plan.players
+        .iter()
+        .map(|steps| {
+            steps
+                .iter()
+                .map(|step| (step.valve.as_str(), step.arrival, step.opening))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn example_plans() {
+    let (plan, _) = example_plan(1);
+    assert_eq!((plan.pressure, plan.minutes), (1651, 30));
+    assert_eq!(
+        steps(&plan),
+        [[
+            ("DD", 1, 2),
+            ("BB", 4, 5),
+            ("JJ", 8, 9),
+            ("HH", 16, 17),
+            ("EE", 20, 21),
+            ("CC", 23, 24)
+        ]]
+    );
+
+    let (plan, _) = example_plan(2);
+    assert_eq!((plan.pressure, plan.minutes), (1707, 26));
+    assert_eq!(
+        steps(&plan),
+        [
+            [("DD", 1, 2), ("HH", 6, 7), ("EE", 10, 11)],
+            [("JJ", 2, 3), ("BB", 6, 7), ("CC", 8, 9)]
+        ]
+    );
+}
+
+// First minutes of the puzzle's walkthroughs.
+const PART1: &str = "\
+== Minute 1 ==
+No valves are open.
+You move to valve DD.
+
+== Minute 2 ==
+No valves are open.
+You open valve DD.
+
+== Minute 3 ==
+Valve DD is open, releasing 20 pressure.
+You move to valve CC.
+
+== Minute 4 ==
+Valve DD is open, releasing 20 pressure.
+You move to valve BB.
+
+== Minute 5 ==
+Valve DD is open, releasing 20 pressure.
+You open valve BB.
+
+== Minute 6 ==
+Valves BB and DD are open, releasing 33 pressure.
+You move to valve AA.
+";
+
+const PART2: &str = "\
+== Minute 1 ==
+No valves are open.
+You move to valve II.
+The elephant moves to valve DD.
+
+== Minute 2 ==
+No valves are open.
+You move to valve JJ.
+The elephant opens valve DD.
+
+== Minute 3 ==
+Valve DD is open, releasing 20 pressure.
+You open valve JJ.
+The elephant moves to valve EE.
+
+== Minute 4 ==
+Valves DD and JJ are open, releasing 41 pressure.
+You move to valve II.
+The elephant moves to valve FF.
+
+== Minute 5 ==
+Valves DD and JJ are open, releasing 41 pressure.
+You move to valve AA.
+The elephant moves to valve GG.
+";
+
+#[test]
+fn replays_follow_the_walkthrough() {
+    let (plan, network) = example_plan(1);
+    let replay = plan.replay(&network);
+    assert!(replay.starts_with(PART1), "{replay}");
+    assert!(replay.contains(
+        "== Minute 21 ==\n\
+         Valves BB, DD, HH, and JJ are open, releasing 76 pressure.\n\
+         You open valve EE.\n"
+    ));
+    assert!(replay.ends_with(
+        "== Minute 30 ==\n\
+         Valves BB, CC, DD, EE, HH, and JJ are open, releasing 81 pressure.\n"
+    ));
+
+    // In the walkthrough, you and the elephant go the other way around
+    let (mut plan, network) = example_plan(2);
+    plan.players.swap(0, 1);
+    let replay = plan.replay(&network);
+    assert!(replay.starts_with(PART2), "{replay}");
+}
@@ -0,0 +1,86 @@
+use day16::common::subsets::SubsetSolver;
+use day16::common::*;
+use shared::random::Lcg;
+use shared::ttable::Replacement;
+
+// Both solvers on the same (reduced) network, starting from "AA". The
+// transposition table is small so that entries get replaced too.
+fn solve(input: &str, max_time: i32, players: usize) -> (i32, i32) {
+    let (graph, start) = reduce_network(parse_network(input), "AA").unwrap();
+    let mut ttable = TTable::new(1 << 16, Replacement::DepthPreferred);
+    let mut backtracker = Solver::new(&graph, &mut ttable, max_time, start);
+    let subsets = SubsetSolver::new(&graph, start, max_time).unwrap();
+    (
+        backtracker.max_pressure_multiplayer(players),
+        subsets.max_pressure_multiplayer(players),
+    )
+}
+
+// A random network of `size` valves, a third of them without flow, tunnels
+// forming a tree plus a few shortcuts.
+fn random_network(seed: u64, size: usize) -> String {
+    let mut lcg = Lcg::new(seed);
+    let mut random = |range: usize| lcg.below(range as u64) as usize;
+    let names = (0..size)
+        .map(|i| {
+            format!(
+                "{}{}",
+                (b'A' + (i / 26) as u8) as char,
+                (b'A' + (i % 26) as u8) as char
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut tunnels = vec![Vec::new(); size];
+    for i in 1..size {
+        let j = random(i);
+        tunnels[i].push(j);
+        tunnels[j].push(i);
+    }
+    for _ in 0..size / 4 {
+        let (a, b) = (random(size), random(size));
+        if a != b && !tunnels[a].contains(&b) {
+            tunnels[a].push(b);
+            tunnels[b].push(a);
+        }
+    }
+    let mut input = String::new();
+    for (i, name) in names.iter().enumerate() {
+        let rate = if i == 0 || random(3) == 0 {
+            0
+        } else {
+            1 + random(25)
+        };
+        let tunnels = tunnels[i]
+            .iter()
+            .map(|j| names[*j].as_str())
+            .collect::<Vec<_>>();
+        input.push_str(&format!(
+            "Valve {name} has flow rate={rate}; tunnels lead to valves {}\n",
+            tunnels.join(", ")
+        ));
+    }
+    input
+}
+
+#[test]
+fn subsets_solve_the_example() {
+    let example = include_str!("../../example.txt");
+    assert_eq!(solve(example, 30, 1), (1651, 1651));
+    assert_eq!(solve(example, 26, 2), (1707, 1707));
+    let (backtracker, subsets) = solve(example, 22, 3);
+    assert_eq!(backtracker, subsets);
+}
+
+#[test]
+fn subsets_agree_with_backtracker() {
+    for seed in 0..20 {
+        let input = random_network(seed, 8 + seed as usize % 5);
+        for (max_time, players) in [(30, 1), (26, 2), (18, 3)] {
+            let (backtracker, subsets) = solve(&input, max_time, players);
+            assert_eq!(
+                backtracker, subsets,
+                "seed {seed}, {players} player(s), {max_time} minutes:\n{input}"
+            );
+        }
+    }
+}
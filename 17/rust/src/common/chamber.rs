@@ -0,0 +1,268 @@
+use std::cmp::min;
+use std::fmt::Display;
+
+use super::shape::{Shape, MAX_WIDTH};
+use super::Jet;
+
+// Rocks fall one at a time in a chamber, pushed sideways by jets of gas, until
+// they land on the floor or on other rocks. Each row of the chamber is a bit
+// mask, like shapes are.
+//
+// The tower grows forever, but rocks only ever reach the top of it: every now
+// and then, rows no rock can reach anymore are dropped to keep the memory low
+// (see `trim`).
+pub struct Chamber {
+    width: usize,
+    shapes: Vec<Shape>,
+    jets: Vec<Jet>,
+    // Where rocks appear: columns from the left wall, and empty rows above
+    // the tower
+    pub spawn_left: usize,
+    pub spawn_gap: usize,
+    // Rows of the tower still in memory, bottom first
+    rows: Vec<u64>,
+    // Rows of the tower below `rows`
+    dropped: u64,
+    // Look for rows to drop once there are this many
+    trim_at: usize,
+    rocks: u64,
+    // Next shape and jet, as indices
+    shape: usize,
+    jet: usize,
+}
+
+// Where a rock came to rest: its shape (index), left column and bottom row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Landing {
+    pub shape: usize,
+    pub x: usize,
+    pub y: u64,
+}
+
+impl Chamber {
+    // Empty chamber, rocks appear two columns from the left wall and three
+    // rows above the tower as in the puzzle.
+    pub fn new(shapes: Vec<Shape>, jets: Vec<Jet>, width: usize) -> Result<Chamber, String> {
+        if width == 0 || width > MAX_WIDTH {
+            return Err(format!(
+                "chamber width must be 1 to {MAX_WIDTH}, not {width}"
+            ));
+        }
+        if shapes.is_empty() || jets.is_empty() {
+            return Err("a chamber needs shapes and jets".to_string());
+        }
+        if let Some(shape) = shapes.iter().find(|shape| shape.width > width) {
+            return Err(format!("shape wider than the chamber ({width}):\n{shape}"));
+        }
+        // Landing rows are counted from the bottom of shapes, which must hold
+        // rock (see `Shape::parse`)
+        if let Some(shape) = shapes.iter().find(|shape| shape.rows.first() == Some(&0)) {
+            return Err(format!("shape with an empty bottom row:\n{shape}"));
+        }
+        Ok(Chamber {
+            width,
+            shapes,
+            jets,
+            spawn_left: 2,
+            spawn_gap: 3,
+            rows: Vec::new(),
+            dropped: 0,
+            trim_at: 64,
+            rocks: 0,
+            shape: 0,
+            jet: 0,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn shapes(&self) -> &[Shape] {
+        &self.shapes
+    }
+
+    pub fn jets(&self) -> &[Jet] {
+        &self.jets
+    }
+
+    pub fn rocks(&self) -> u64 {
+        self.rocks
+    }
+
+    pub fn height(&self) -> u64 {
+        self.dropped + self.rows.len() as u64
+    }
+
+    pub fn next_shape(&self) -> usize {
+        self.shape
+    }
+
+    pub fn next_jet(&self) -> usize {
+        self.jet
+    }
+
+    // Bit mask of a row filled from wall to wall.
+    pub fn full_row(&self) -> u64 {
+        match self.width {
+            MAX_WIDTH => u64::MAX,
+            width => (1 << width) - 1,
+        }
+    }
+
+    // Counts `rocks` more rocks, that made the tower `height` rows taller
+    // without changing what its top looks like. This is how to skip ahead
+    // once the same rocks keep falling the same way.
+    pub fn fast_forward(&mut self, rocks: u64, height: u64) {
+        self.rocks += rocks;
+        self.dropped += height;
+    }
+
+    // Row `y` of the tower, counting from the floor. Dropped rows are solid.
+    pub fn row(&self, y: u64) -> u64 {
+        if y < self.dropped {
+            return self.full_row();
+        }
+        *self.rows.get((y - self.dropped) as usize).unwrap_or(&0)
+    }
+
+    fn collides(&self, shape: &Shape, x: usize, y: u64) -> bool {
+        shape
+            .rows
+            .iter()
+            .enumerate()
+            .any(|(i, row)| self.row(y + i as u64) & (row << x) != 0)
+    }
+
+    // Lets the next rock fall until it lands. Shapes too wide to appear at
+    // `spawn_left` appear against the right wall instead.
+    pub fn drop_rock(&mut self) -> Landing {
+        let shape = &self.shapes[self.shape];
+        let mut x = min(self.spawn_left, self.width - shape.width);
+        let mut y = self.height() + self.spawn_gap as u64;
+        loop {
+            // Pushed by the jet, if there's room
+            let try_x = match self.jets[self.jet] {
+                Jet::Left => x.checked_sub(1),
+                Jet::Right => Some(x + 1).filter(|x| x + shape.width <= self.width),
+            };
+            self.jet = (self.jet + 1) % self.jets.len();
+            if let Some(try_x) = try_x {
+                if !self.collides(shape, try_x, y) {
+                    x = try_x;
+                }
+            }
+            // Then falls, or lands
+            if y == 0 || self.collides(shape, x, y - 1) {
+                break;
+            }
+            y -= 1;
+        }
+
+        let landing = Landing {
+            shape: self.shape,
+            x,
+            y,
+        };
+        self.land(landing);
+        landing
+    }
+
+    fn land(&mut self, landing: Landing) {
+        let shape = &self.shapes[landing.shape];
+        let bottom = (landing.y - self.dropped) as usize;
+        if self.rows.len() < bottom + shape.height() {
+            self.rows.resize(bottom + shape.height(), 0);
+        }
+        for (i, row) in shape.rows.iter().enumerate() {
+            self.rows[bottom + i] |= row << landing.x;
+        }
+        // Empty rows at the top of a shape don't make the tower any taller
+        while self.rows.last() == Some(&0) {
+            self.rows.pop();
+        }
+
+        self.rocks += 1;
+        self.shape = (self.shape + 1) % self.shapes.len();
+        if self.rows.len() >= self.trim_at {
+            self.trim();
+            self.trim_at = 2 * self.rows.len() + 64;
+        }
+    }
+
+    // Air rocks can reach, for each row from the top down, `depth` rows at
+    // most. Rocks move down and sideways, so none of their cells can get
+    // anywhere a single cell couldn't: going down the tower row by row, air
+    // can be reached right below reachable air, then sideways as far as the
+    // air goes. This stops at the first row without reachable air: it and all
+    // the rows below are out of reach.
+    fn reachable(&self, depth: usize) -> Vec<u64> {
+        let full = self.full_row();
+        let mut reach = vec![];
+        let mut above = full;
+        for row in self.rows.iter().rev().take(depth) {
+            let air = !row & full;
+            let mut next = above & air;
+            loop {
+                let spread = (next | next << 1 | next >> 1) & air;
+                if spread == next {
+                    break;
+                }
+                next = spread;
+            }
+            if next == 0 {
+                break;
+            }
+            reach.push(next);
+            above = next;
+        }
+        reach
+    }
+
+    // Drops the rows no rock can reach anymore.
+    fn trim(&mut self) {
+        let lowest = self.rows.len() - self.reachable(usize::MAX).len();
+        self.rows.drain(..lowest);
+        self.dropped += lowest as u64;
+    }
+
+    // The top `depth` rows of the tower at most, down to the lowest row rocks
+    // can reach, bottom row first. Air out of reach is as good as rock, so
+    // it's filled.
+    pub fn surface(&self, depth: usize) -> Vec<u64> {
+        let full = self.full_row();
+        let mut surface = self
+            .reachable(depth)
+            .iter()
+            .map(|reach| full & !reach)
+            .collect::<Vec<_>>();
+        surface.reverse();
+        surface
+    }
+}
+
+// The tower as drawn in the puzzle, top first:
+//
+//   |..#....|
+//   |.###...|
+//   |..#....|
+//   |####...|
+//   +-------+
+impl Display for Chamber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows.iter().rev() {
+            write!(f, "|")?;
+            for x in 0..self.width {
+                match row & (1 << x) != 0 {
+                    true => write!(f, "#")?,
+                    false => write!(f, ".")?,
+                }
+            }
+            writeln!(f, "|")?;
+        }
+        match self.dropped {
+            0 => writeln!(f, "+{}+", "-".repeat(self.width)),
+            dropped => writeln!(f, "|{}| {dropped} rows below", "~".repeat(self.width)),
+        }
+    }
+}
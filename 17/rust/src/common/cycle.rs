@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use super::chamber::Chamber;
+
+// Where rocks land next only depends on the next shape, the next jet, and the
+// top of the tower rocks can still reach: its surface profile (see
+// `Chamber::surface`). Sooner or later, the same state comes back, and from
+// there the same rocks keep falling the same way: every `length` rocks, the
+// tower grows by `height` rows. Everything in between can be skipped.
+//
+// The surface can go all the way down, e.g. along a wall rocks never get to,
+// so it wouldn't ever repeat. Instead, the state only holds the rows rocks
+// looked into so far below the top of the tower (down to the row under where
+// they landed). When a rock looks deeper, states seen before aren't enough
+// and we start over with more rows. Rocks of a cycle then never look deeper
+// than the state, and neither will the next cycles: skipping them is exact.
+//
+// Unlike looking for rows blocked from wall to wall, this works whatever the
+// jets, shapes and width.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    // Rocks fallen when the state was first seen
+    pub start: u64,
+    // Rocks per cycle, and how much taller they make the tower
+    pub length: u64,
+    pub height: u64,
+}
+
+impl Chamber {
+    // Drops rocks until there are `rocks` of them, skipping whole cycles as
+    // soon as one shows up. Returns the cycle, if there was one before the
+    // end.
+    pub fn drop_rocks(&mut self, rocks: u64) -> Option<Cycle> {
+        // (shape, jet, surface) -> (rocks, height)
+        let mut seen = HashMap::<(usize, usize, Vec<u64>), (u64, u64)>::new();
+        let mut depth = 1;
+        let mut cycle = None;
+        while self.rocks() < rocks {
+            if cycle.is_none() {
+                let state = (self.next_shape(), self.next_jet(), self.surface(depth));
+                if let Some(&(start, height)) = seen.get(&state) {
+                    let found = Cycle {
+                        start,
+                        length: self.rocks() - start,
+                        height: self.height() - height,
+                    };
+                    let cycles = (rocks - self.rocks()) / found.length;
+                    self.fast_forward(cycles * found.length, cycles * found.height);
+                    cycle = Some(found);
+                    continue;
+                }
+                seen.insert(state, (self.rocks(), self.height()));
+            }
+
+            let height = self.height();
+            let landing = self.drop_rock();
+            let looked = (height + 1 - landing.y) as usize;
+            if looked > depth {
+                depth = looked;
+                seen.clear();
+            }
+        }
+        cycle
+    }
+}
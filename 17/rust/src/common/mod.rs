@@ -0,0 +1,31 @@
+pub mod chamber;
+pub mod cycle;
+pub mod shape;
+
+// Jets of hot gas push rocks one column at a time, in the order of the input:
+//
+//   >>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Jet {
+    Left,
+    Right,
+}
+
+// Whitespace is skipped, anything else is an error.
+pub fn parse_jets(input: &str) -> Result<Vec<Jet>, String> {
+    let jets = input
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .map(|ch| match ch {
+            '<' => Ok(Jet::Left),
+            '>' => Ok(Jet::Right),
+            _ => Err(format!(
+                "unexpected '{ch}' in jet pattern, expect '<' or '>'"
+            )),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if jets.is_empty() {
+        return Err("empty jet pattern".to_string());
+    }
+    Ok(jets)
+}
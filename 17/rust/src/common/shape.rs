@@ -0,0 +1,102 @@
+use std::fmt::Display;
+
+// Rocks of the puzzle, in the order they fall.
+pub const ROCKS: &str = "\
+####
+
+.#.
+###
+.#.
+
+..#
+..#
+###
+
+#
+#
+#
+#
+
+##
+##
+";
+
+// Widest shape (and chamber) a row can hold.
+pub const MAX_WIDTH: usize = 64;
+
+// A rock, as a stencil: one bit mask per row, bottom row first. Bit `x` is
+// the `x`-th column from the left edge of the shape (Lsb-style, so the
+// stencils read mirrored).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Shape {
+    pub width: usize,
+    pub rows: Vec<u64>,
+}
+
+impl Shape {
+    // From ASCII art as in the puzzle, top row first: '#' for rock, '.' for
+    // air. Empty rows at the bottom are dropped: rocks land on their lowest
+    // row with rock.
+    pub fn parse(art: &str) -> Result<Shape, String> {
+        let mut rows = Vec::new();
+        let mut width = 0;
+        for line in art.lines().map(|line| line.trim()) {
+            let mut row = 0;
+            for (x, ch) in line.chars().enumerate() {
+                match ch {
+                    '#' if x < MAX_WIDTH => row |= 1 << x,
+                    '#' => return Err(format!("shape wider than {MAX_WIDTH}:\n{art}")),
+                    '.' => (),
+                    _ => return Err(format!("unexpected '{ch}' in shape, expect '#' or '.'")),
+                }
+            }
+            width = width.max(line.len());
+            rows.push(row);
+        }
+        if rows.iter().all(|row| *row == 0) {
+            return Err(format!("shape without any rock:\n{art}"));
+        }
+        rows.reverse();
+        let bottom = rows.iter().take_while(|row| **row == 0).count();
+        rows.drain(..bottom);
+        Ok(Shape { width, rows })
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+}
+
+// Shapes separated by blank lines, like `ROCKS`.
+pub fn parse_shapes(input: &str) -> Result<Vec<Shape>, String> {
+    let mut shapes = Vec::new();
+    let mut art = String::new();
+    for line in input.lines().chain([""]) {
+        if !line.trim().is_empty() {
+            art.push_str(line);
+            art.push('\n');
+        } else if !art.is_empty() {
+            shapes.push(Shape::parse(&art)?);
+            art.clear();
+        }
+    }
+    if shapes.is_empty() {
+        return Err("no shapes".to_string());
+    }
+    Ok(shapes)
+}
+
+impl Display for Shape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows.iter().rev() {
+            for x in 0..self.width {
+                match row & (1 << x) != 0 {
+                    true => write!(f, "#")?,
+                    false => write!(f, ".")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
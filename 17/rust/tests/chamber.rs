@@ -0,0 +1,132 @@
+use day17::common::chamber::Chamber;
+use day17::common::parse_jets;
+use day17::common::shape::{parse_shapes, Shape, ROCKS};
+
+const EXAMPLE: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+
+fn example() -> Chamber {
+    let shapes = parse_shapes(ROCKS).unwrap();
+    Chamber::new(shapes, parse_jets(EXAMPLE).unwrap(), 7).unwrap()
+}
+
+#[test]
+fn example_tower() {
+    let mut chamber = example();
+    for _ in 0..10 {
+        chamber.drop_rock();
+    }
+    // As drawn in the puzzle
+    assert_eq!(
+        chamber.to_string(),
+        "\
+|....#..|
+|....#..|
+|....##.|
+|##..##.|
+|######.|
+|.###...|
+|..#....|
+|.####..|
+|....##.|
+|....##.|
+|....#..|
+|..#.#..|
+|..#.#..|
+|#####..|
+|..###..|
+|...#...|
+|..####.|
++-------+
+"
+    );
+
+    while chamber.rocks() < 2022 {
+        chamber.drop_rock();
+    }
+    assert_eq!(chamber.height(), 3068);
+}
+
+#[test]
+fn custom_shapes() {
+    // Single cells appearing against the right wall (as far right as they
+    // can) pile up in the last column
+    let shapes = parse_shapes("#").unwrap();
+    let mut chamber = Chamber::new(shapes, parse_jets(">").unwrap(), 16).unwrap();
+    chamber.spawn_left = 100;
+    for rock in 1..=100 {
+        let landing = chamber.drop_rock();
+        assert_eq!((landing.x, landing.y), (15, rock - 1));
+    }
+    assert_eq!(chamber.height(), 100);
+
+    // Wall to wall bars, appearing where they can
+    let shapes = parse_shapes("################\n\n.#\n##").unwrap();
+    let mut chamber = Chamber::new(shapes, parse_jets("<<>").unwrap(), 16).unwrap();
+    chamber.spawn_left = 5;
+    let landing = chamber.drop_rock();
+    assert_eq!((landing.x, landing.y), (0, 0));
+    let landing = chamber.drop_rock();
+    assert_eq!((landing.x, landing.y), (3, 1));
+    assert_eq!(chamber.height(), 3);
+
+    assert!(Chamber::new(parse_shapes("####").unwrap(), vec![], 7).is_err());
+    assert!(Chamber::new(
+        parse_shapes("########").unwrap(),
+        parse_jets("<").unwrap(),
+        7
+    )
+    .is_err());
+    assert!(parse_shapes("#x#").is_err());
+}
+
+#[test]
+fn empty_bottom_rows() {
+    // Dropped as the shapes are parsed: rocks land on the row with rock,
+    // even once the rows below are trimmed away
+    let shapes = parse_shapes("##\n..\n\n####\n\n#\n#\n.").unwrap();
+    assert_eq!(shapes[0], parse_shapes("##").unwrap()[0]);
+    let new = || Chamber::new(shapes.clone(), parse_jets(">>>>>><<><").unwrap(), 4).unwrap();
+    let mut chamber = new();
+    while chamber.rocks() < 600 {
+        chamber.drop_rock();
+    }
+    let mut fast = new();
+    fast.drop_rocks(600);
+    assert_eq!((chamber.height(), fast.height()), (668, 668));
+
+    let shape = Shape {
+        width: 2,
+        rows: vec![0, 3],
+    };
+    assert!(Chamber::new(vec![shape], parse_jets("<").unwrap(), 7).is_err());
+}
+
+#[test]
+fn cycles() {
+    let mut chamber = example();
+    let cycle = chamber.drop_rocks(1_000_000_000_000).unwrap();
+    assert_eq!((cycle.length, cycle.height), (35, 53));
+    assert_eq!(chamber.height(), 1_514_285_714_288);
+
+    // Same tower as dropping rocks one by one, whatever the width and shapes,
+    // wall to wall rows or not
+    for (width, shapes) in [(7, ROCKS), (16, ROCKS), (9, "#####\n\n#.#\n###\n\n##\n#.")] {
+        let new = || {
+            let shapes = parse_shapes(shapes).unwrap();
+            Chamber::new(shapes, parse_jets(EXAMPLE).unwrap(), width).unwrap()
+        };
+        let mut slow = new();
+        for rocks in [1, 100, 2022, 5000] {
+            while slow.rocks() < rocks {
+                slow.drop_rock();
+            }
+            let mut fast = new();
+            let cycle = fast.drop_rocks(rocks);
+            assert_eq!(fast.height(), slow.height(), "{rocks} rocks, width {width}");
+            assert!(
+                cycle.is_some() || rocks < 2022,
+                "{rocks} rocks, width {width}"
+            );
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use regex::Regex;
+use shared::ttable::{self, Replacement};
 use std::cmp::{max, min};
-use std::collections::HashMap;
+use std::env;
 use std::io;
 
 // Wao, that was tough, and I didn't solve it alone, to be frank.
@@ -173,28 +174,27 @@ impl Entry {
     }
 }
 
-// Transposition table maps an Entry to a max pressure (i32). We store only
-// exact solutions here.
-type TTable = HashMap<Entry, usize>;
+// Transposition table maps an Entry to max geodes (usize). We store only
+// exact solutions here, in a table of fixed size: entries that don't fit get
+// computed again.
+type TTable = ttable::TTable<Entry, usize>;
 
 struct BlueprintSolver<'a> {
     blueprint: &'a Blueprint,
-    t_table: TTable,
-    tt_hits: usize,
+    t_table: &'a mut TTable,
 }
 
-impl BlueprintSolver<'_> {
-    fn with(blueprint: &Blueprint) -> BlueprintSolver {
-        BlueprintSolver {
-            blueprint,
-            t_table: TTable::default(),
-            tt_hits: 0,
-        }
+impl<'a> BlueprintSolver<'a> {
+    fn with(blueprint: &'a Blueprint, t_table: &'a mut TTable) -> BlueprintSolver<'a> {
+        // Entries are only valid for one blueprint
+        t_table.clear();
+        BlueprintSolver { blueprint, t_table }
     }
 }
 
 struct Solver {
     blueprints: Vec<Blueprint>,
+    t_table: TTable,
 }
 
 struct Builds {
@@ -227,10 +227,10 @@ impl Iterator for Builds {
 // Upper bound function available?
 // Given a known - best, we can compute if we can reach it, by multiplying back and counting available resources.
 impl Solver {
-    fn print_part1(&self, time_left: i32) {
+    fn print_part1(&mut self, time_left: i32) {
         let mut quality = 0;
         for bp in self.blueprints.iter() {
-            let mut bps = BlueprintSolver::with(bp);
+            let mut bps = BlueprintSolver::with(bp, &mut self.t_table);
             let geodes = bps.max_geodes(&mut State::default(), time_left);
 
             // Optional display:
@@ -240,10 +240,10 @@ impl Solver {
         println!("Part1: Quality level: {}", quality);
     }
 
-    fn print_part2(&self, time_left: i32) {
+    fn print_part2(&mut self, time_left: i32) {
         let mut all_geodes = 1;
         for bp in &self.blueprints[..3] {
-            let mut bps = BlueprintSolver::with(bp);
+            let mut bps = BlueprintSolver::with(bp, &mut self.t_table);
             let geodes = bps.max_geodes(&mut State::default(), time_left);
 
             // Optional display:
@@ -302,8 +302,7 @@ impl BlueprintSolver<'_> {
         // Check transposition tables for known exact entry
         let entry = Entry::from_state(self.blueprint, state, time_left);
         if let Some(geode) = self.t_table.get(&entry) {
-            self.tt_hits += 1;
-            return *geode;
+            return geode;
         }
 
         // try harvesting geode for the rest of time first
@@ -323,7 +322,7 @@ impl BlueprintSolver<'_> {
             state.cancel_harvest(harvest_time);
         }
 
-        self.t_table.insert(entry, geode);
+        self.t_table.insert(entry, geode, time_left as u32);
         geode
     }
 }
@@ -358,7 +357,25 @@ fn main() {
 
     let blueprints: Vec<Blueprint> = captures.iter().map(Blueprint::from_capture).collect();
 
-    let solver = Solver { blueprints };
+    // Optional: memory for the transposition table in MB, and which entries it
+    // keeps once full. The latest ones are the default: the search hardly
+    // comes back to old states, keeping the deepest ones is slower.
+    let memory = env::args().nth(1).map_or(ttable::DEFAULT_MEMORY, |arg| {
+        arg.parse::<usize>()
+            .expect("Error 1st positional argument: expect memory in MB")
+            << 20
+    });
+    let replacement = match env::args().nth(2).as_deref() {
+        None | Some("always-replace") => Replacement::AlwaysReplace,
+        Some("depth-preferred") => Replacement::DepthPreferred,
+        _ => panic!("Error 2nd positional argument: expect 'always-replace' or 'depth-preferred'"),
+    };
+
+    let mut solver = Solver {
+        blueprints,
+        t_table: TTable::with_memory(memory, replacement),
+    };
     solver.print_part1(24);
     solver.print_part2(32);
+    eprintln!("Transposition table: {}", solver.t_table.stats());
 }
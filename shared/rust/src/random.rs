@@ -0,0 +1,23 @@
+// Small linear congruential generator, good enough to scatter rocks, sensors
+// or valves around in benchmarks and tests. Seeded, so runs can be repeated.
+//
+// Resources:
+// - https://en.wikipedia.org/wiki/Linear_congruential_generator (MMIX constants)
+pub struct Lcg {
+    state: u64,
+}
+
+impl Lcg {
+    pub fn new(seed: u64) -> Lcg {
+        Lcg { state: seed }
+    }
+
+    // Next number in [0, range). The high bits are the most random ones.
+    pub fn below(&mut self, range: u64) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.state >> 33) % range
+    }
+}
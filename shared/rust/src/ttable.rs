@@ -0,0 +1,176 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+use std::mem::size_of;
+use std::ops::AddAssign;
+
+// Transposition table with a fixed number of slots, so a search runs within a
+// set memory budget instead of growing until the machine gives up.
+//
+// Each key goes to a single slot picked by its hash. When two keys want the
+// same slot (a collision), the replacement policy decides which one stays:
+//
+// - `DepthPreferred` keeps the entry with the deepest search below it, which
+//   is the most expensive to compute again. Ties go to the new entry.
+// - `AlwaysReplace` keeps the new entry, recent positions tend to come back
+//   soon.
+//
+// Losing an entry only costs computing it again: the table never gives a
+// wrong answer, as long as it only holds exact values.
+//
+// Resources:
+// - https://www.chessprogramming.org/Transposition_Table
+// - https://www.chessprogramming.org/Replacement_Strategies
+
+// What a table takes when no budget is given.
+pub const DEFAULT_MEMORY: usize = 64 << 20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Replacement {
+    DepthPreferred,
+    AlwaysReplace,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    // Lookups that found their key
+    pub hits: u64,
+    // Lookups that didn't
+    pub misses: u64,
+    // Inserts into a slot holding another key, whichever was kept
+    pub collisions: u64,
+}
+
+impl AddAssign for Stats {
+    fn add_assign(&mut self, other: Stats) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.collisions += other.collisions;
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lookups = self.hits + self.misses;
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate), {} collisions",
+            self.hits,
+            self.misses,
+            match lookups {
+                0 => 0.0,
+                _ => 100.0 * self.hits as f64 / lookups as f64,
+            },
+            self.collisions
+        )
+    }
+}
+
+struct Slot<K, V> {
+    key: K,
+    value: V,
+    depth: u32,
+}
+
+pub struct TTable<K, V> {
+    slots: Vec<Option<Slot<K, V>>>,
+    replacement: Replacement,
+    len: usize,
+    stats: Stats,
+}
+
+impl<K: Hash + Eq, V: Copy> TTable<K, V> {
+    // Table with `capacity` slots (at least one).
+    pub fn new(capacity: usize, replacement: Replacement) -> TTable<K, V> {
+        TTable {
+            slots: (0..capacity.max(1)).map(|_| None).collect(),
+            replacement,
+            len: 0,
+            stats: Stats::default(),
+        }
+    }
+
+    // As many slots as fit in `bytes`, for keys that don't hold heap memory.
+    pub fn with_memory(bytes: usize, replacement: Replacement) -> TTable<K, V> {
+        TTable::with_memory_and_heap(bytes, 0, replacement)
+    }
+
+    // Same, for keys also holding `heap` bytes each on the heap (like a `Vec`),
+    // so a full table stays within `bytes` (save for the allocator's own
+    // overhead).
+    pub fn with_memory_and_heap(
+        bytes: usize,
+        heap: usize,
+        replacement: Replacement,
+    ) -> TTable<K, V> {
+        TTable::new(
+            bytes / (size_of::<Option<Slot<K, V>>>() + heap),
+            replacement,
+        )
+    }
+
+    fn index(&self, key: &K) -> usize {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        (hasher.finish() % self.slots.len() as u64) as usize
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let index = self.index(key);
+        match &self.slots[index] {
+            Some(slot) if slot.key == *key => {
+                self.stats.hits += 1;
+                Some(slot.value)
+            }
+            _ => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    // `depth` tells how much work went into `value`, the deeper the better
+    // (e.g. the time left in the search).
+    pub fn insert(&mut self, key: K, value: V, depth: u32) {
+        let index = self.index(&key);
+        match &self.slots[index] {
+            None => self.len += 1,
+            Some(slot) if slot.key == key => (),
+            Some(slot) => {
+                self.stats.collisions += 1;
+                if self.replacement == Replacement::DepthPreferred && slot.depth > depth {
+                    return;
+                }
+            }
+        }
+        self.slots[index] = Some(Slot { key, value, depth });
+    }
+
+    pub fn clear(&mut self) {
+        self.slots.iter_mut().for_each(|slot| *slot = None);
+        self.len = 0;
+    }
+
+    // Entries in the table
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+}
+
+impl<K: Hash + Eq, V: Copy> Default for TTable<K, V> {
+    fn default() -> Self {
+        TTable::with_memory(DEFAULT_MEMORY, Replacement::DepthPreferred)
+    }
+}
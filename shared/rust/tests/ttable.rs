@@ -0,0 +1,88 @@
+use shared::ttable::{Replacement, Stats, TTable};
+
+#[test]
+fn counts_hits_misses_and_collisions() {
+    // A single slot: every other key collides
+    let mut table = TTable::new(1, Replacement::DepthPreferred);
+    assert_eq!(table.get(&1), None);
+    table.insert(1, 10, 5);
+    assert_eq!(table.get(&1), Some(10));
+    assert_eq!(table.get(&2), None);
+    // Same key again isn't a collision
+    table.insert(1, 11, 5);
+    table.insert(2, 20, 1);
+    assert_eq!(
+        table.stats(),
+        Stats {
+            hits: 1,
+            misses: 2,
+            collisions: 1
+        }
+    );
+    assert_eq!(
+        table.stats().to_string(),
+        "1 hits, 2 misses (33.3% hit rate), 1 collisions"
+    );
+}
+
+#[test]
+fn depth_preferred_keeps_the_deepest() {
+    let mut table = TTable::new(1, Replacement::DepthPreferred);
+    table.insert("deep", 1, 5);
+    table.insert("shallow", 2, 4);
+    assert_eq!(table.get(&"deep"), Some(1));
+    assert_eq!(table.get(&"shallow"), None);
+    // Ties go to the new entry
+    table.insert("new", 3, 5);
+    assert_eq!(table.get(&"deep"), None);
+    assert_eq!(table.get(&"new"), Some(3));
+    table.insert("deeper", 4, 6);
+    assert_eq!(table.get(&"deeper"), Some(4));
+    // The same key is always updated
+    table.insert("deeper", 5, 0);
+    assert_eq!(table.get(&"deeper"), Some(5));
+    assert_eq!(table.len(), 1);
+}
+
+#[test]
+fn always_replace_keeps_the_latest() {
+    let mut table = TTable::new(1, Replacement::AlwaysReplace);
+    table.insert("deep", 1, 5);
+    table.insert("shallow", 2, 0);
+    assert_eq!(table.get(&"deep"), None);
+    assert_eq!(table.get(&"shallow"), Some(2));
+    assert_eq!(table.stats().collisions, 1);
+    assert_eq!(table.len(), 1);
+}
+
+#[test]
+fn clear_and_len() {
+    let mut table = TTable::new(1024, Replacement::DepthPreferred);
+    assert!(table.is_empty());
+    for key in 0..100u32 {
+        table.insert(key, key * 2, 0);
+    }
+    // Keys may share slots, each slot holds one of them
+    let found = (0..100u32).filter(|key| table.get(key).is_some()).count();
+    assert_eq!(table.len(), found);
+    assert!(found > 90);
+    assert_eq!(table.capacity(), 1024);
+
+    table.clear();
+    assert!(table.is_empty());
+    assert_eq!((0..100u32).filter_map(|key| table.get(&key)).count(), 0);
+    assert_eq!(table.capacity(), 1024);
+}
+
+#[test]
+fn memory_budget() {
+    let table = TTable::<u64, u64>::with_memory(1 << 20, Replacement::AlwaysReplace);
+    let with_heap =
+        TTable::<u64, u64>::with_memory_and_heap(1 << 20, 64, Replacement::AlwaysReplace);
+    assert!(table.capacity() > with_heap.capacity());
+    assert!(table.capacity() * 8 < 1 << 20);
+    assert_eq!(
+        TTable::<u64, u64>::with_memory(0, Replacement::AlwaysReplace).capacity(),
+        1
+    );
+}